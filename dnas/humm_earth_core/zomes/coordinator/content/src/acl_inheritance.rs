@@ -0,0 +1,78 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{encrypted_content::get_latest_encrypted_content, hive_policy::get_latest_hive_policy};
+
+/// The entity ACL of a header merged with the latest revisions of everything it inherits from.
+/// Unlike validation, which reads the pinned parent revisions, queries follow parent updates.
+pub fn get_effective_acl(header: &EncryptedContentHeader) -> ExternResult<Acl> {
+    Ok(get_effective_acls(header)?.0)
+}
+
+/// The public key ACL of a header merged with the latest revisions of everything it inherits
+/// from.
+pub fn get_effective_public_key_acl(header: &EncryptedContentHeader) -> ExternResult<Acl> {
+    Ok(get_effective_acls(header)?.1)
+}
+
+fn get_effective_acls(header: &EncryptedContentHeader) -> ExternResult<(Acl, Acl)> {
+    let mut acl = header.acl.clone();
+    let mut public_key_acl = header.public_key_acl.clone();
+    let mut parent = header.inherits_from.clone();
+    for _ in 0..MAX_ACL_INHERITANCE_DEPTH {
+        let (parent_acl, parent_public_key_acl, next_parent) = match parent {
+            None => break,
+            Some(AclParent::HivePolicy { hash }) => {
+                let (hive_policy, _) = get_latest_hive_policy(original_action_hash(hash)?)?;
+                (hive_policy.acl, hive_policy.public_key_acl, None)
+            }
+            Some(AclParent::Content { hash }) => {
+                let (encrypted_content, _) =
+                    get_latest_encrypted_content(original_action_hash(hash)?)?;
+                (
+                    encrypted_content.header.acl,
+                    encrypted_content.header.public_key_acl,
+                    encrypted_content.header.inherits_from,
+                )
+            }
+        };
+        acl = acl.inherit(&parent_acl);
+        public_key_acl = public_key_acl.inherit(&parent_public_key_acl);
+        parent = next_parent;
+    }
+    Ok((acl, public_key_acl))
+}
+
+/// Follows the `AclInheritance` links down from the given parents and returns the original
+/// hashes of the inheriting content of one content type.
+pub fn get_inheriting_content_hashes(
+    parents: Vec<ActionHash>,
+    content_type: &str,
+) -> ExternResult<Vec<ActionHash>> {
+    let mut visited: Vec<ActionHash> = parents.clone();
+    let mut hashes: Vec<ActionHash> = vec![];
+    let mut frontier = parents;
+    for _ in 0..MAX_ACL_INHERITANCE_DEPTH {
+        let mut next_frontier: Vec<ActionHash> = vec![];
+        for parent in frontier {
+            for link in get_links(parent, LinkTypes::AclInheritance, None)? {
+                let Some(child) = link.target.clone().into_action_hash() else {
+                    continue;
+                };
+                if visited.contains(&child) {
+                    continue;
+                }
+                visited.push(child.clone());
+                if link.tag.0 == content_type.as_bytes() {
+                    hashes.push(child.clone());
+                }
+                next_frontier.push(child);
+            }
+        }
+        if next_frontier.is_empty() {
+            break;
+        }
+        frontier = next_frontier;
+    }
+    Ok(hashes)
+}
@@ -0,0 +1,200 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    encrypted_content::{commit_encrypted_content_update, get_latest_encrypted_content},
+    linking::{
+        acl_links::update_acl_links,
+        hive_link::{get_hive_content_hashes, get_hive_content_types},
+    },
+};
+
+const DEFAULT_BULK_UPDATE_BATCH_SIZE: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AclRoleEntity {
+    pub acl_role: String, // "Admin", "Writer" or "Reader"
+    pub entity_id: String,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>, // only used when adding, `None` grants without a time limit
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkUpdateAclInput {
+    pub hive_id: String,
+    pub content_type: Option<String>,
+    pub add: Vec<AclRoleEntity>,
+    pub remove: Vec<AclRoleEntity>,
+    pub cursor: Option<ActionHash>,
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkUpdateAclItemReport {
+    pub original_hash: String,
+    pub previous_hash: Option<String>,
+    pub updated_hash: Option<String>,
+    pub status: String, // "Updated", "Unchanged" or "Failed"
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkUpdateAclResponse {
+    pub items: Vec<BulkUpdateAclItemReport>,
+    pub total: usize,
+    pub next_cursor: Option<ActionHash>,
+}
+
+/// Adds and removes entities from the ACL of every content in a hive, optionally limited to one
+/// content type. Contents are processed in the order of their original hashes and each call
+/// commits one batch of revisions after the `cursor` hash; call again with `next_cursor` until it
+/// is `None`.
+#[hdk_extern]
+pub fn bulk_update_acl(input: BulkUpdateAclInput) -> ExternResult<BulkUpdateAclResponse> {
+    for change in input.add.iter().chain(input.remove.iter()) {
+        if !["Admin", "Writer", "Reader"].contains(&change.acl_role.as_str()) {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Invalid acl_role for a bulk update: \"{}\"",
+                change.acl_role
+            ))));
+        }
+    }
+    let batch_size = input.batch_size.unwrap_or(DEFAULT_BULK_UPDATE_BATCH_SIZE);
+    if batch_size == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The batch size of a bulk update must be at least 1"
+        ))));
+    }
+
+    let content_types = match input.content_type {
+        Some(content_type) => vec![content_type],
+        None => get_hive_content_types(input.hive_id.clone())?,
+    };
+    let mut original_hashes: Vec<ActionHash> = vec![];
+    for content_type in content_types {
+        original_hashes.extend(get_hive_content_hashes(
+            input.hive_id.clone(),
+            content_type,
+        )?);
+    }
+
+    // links come back in no particular order, sorting keeps the cursor stable between calls
+    // even when content is added to the hive in the meantime
+    original_hashes.sort_by_key(|hash| hash.to_string());
+    original_hashes.dedup();
+
+    let start = match &input.cursor {
+        Some(cursor) => {
+            original_hashes.partition_point(|hash| hash.to_string() <= cursor.to_string())
+        }
+        None => 0,
+    };
+    let end = start.saturating_add(batch_size).min(original_hashes.len());
+    // an item that can't be read is reported as failed, but once a revision is committed any
+    // error fails the whole call so that no revision is left without its ACL links
+    let mut items: Vec<BulkUpdateAclItemReport> = vec![];
+    for original_hash in original_hashes[start..end].iter() {
+        let (previous_encrypted_content, previous_hash) =
+            match get_latest_encrypted_content(original_hash.clone()) {
+                Ok(latest) => latest,
+                Err(e) => {
+                    items.push(BulkUpdateAclItemReport {
+                        original_hash: original_hash.to_string(),
+                        previous_hash: None,
+                        updated_hash: None,
+                        status: String::from("Failed"),
+                        error: Some(format!("{:?}", e)),
+                    });
+                    continue;
+                }
+            };
+        items.push(bulk_update_acl_item(
+            original_hash.clone(),
+            previous_encrypted_content,
+            previous_hash,
+            &input.add,
+            &input.remove,
+        )?);
+    }
+
+    Ok(BulkUpdateAclResponse {
+        items,
+        total: original_hashes.len(),
+        next_cursor: (end < original_hashes.len()).then(|| original_hashes[end - 1].clone()),
+    })
+}
+
+fn bulk_update_acl_item(
+    original_hash: ActionHash,
+    previous_encrypted_content: EncryptedContent,
+    previous_hash: ActionHash,
+    add: &[AclRoleEntity],
+    remove: &[AclRoleEntity],
+) -> ExternResult<BulkUpdateAclItemReport> {
+    let mut updated_encrypted_content = previous_encrypted_content.clone();
+    apply_acl_changes(&mut updated_encrypted_content.header.acl, add, remove);
+    if updated_encrypted_content == previous_encrypted_content {
+        return Ok(BulkUpdateAclItemReport {
+            original_hash: original_hash.to_string(),
+            previous_hash: Some(previous_hash.to_string()),
+            updated_hash: None,
+            status: String::from("Unchanged"),
+            error: None,
+        });
+    }
+
+    let (updated_hash, _) =
+        commit_encrypted_content_update(previous_hash.clone(), &mut updated_encrypted_content)?;
+    update_acl_links(
+        &previous_encrypted_content,
+        &updated_encrypted_content,
+        original_hash.clone(),
+    )?;
+
+    Ok(BulkUpdateAclItemReport {
+        original_hash: original_hash.to_string(),
+        previous_hash: Some(previous_hash.to_string()),
+        updated_hash: Some(updated_hash.to_string()),
+        status: String::from("Updated"),
+        error: None,
+    })
+}
+
+fn apply_acl_changes(acl: &mut Acl, add: &[AclRoleEntity], remove: &[AclRoleEntity]) {
+    for change in remove {
+        if let Some(members) = acl_role_members(acl, &change.acl_role) {
+            members.retain(|id| id != &change.entity_id);
+        }
+        // the expiry belongs to the principal, drop it once no role is left
+        let still_granted = [&acl.admin, &acl.writer, &acl.reader]
+            .iter()
+            .any(|members| members.contains(&change.entity_id));
+        if !still_granted {
+            acl.expires_at.remove(&change.entity_id);
+        }
+    }
+    for change in add {
+        if let Some(members) = acl_role_members(acl, &change.acl_role) {
+            if !members.contains(&change.entity_id) {
+                members.push(change.entity_id.clone());
+            }
+        }
+        match change.expires_at {
+            Some(expires_at) => {
+                acl.expires_at.insert(change.entity_id.clone(), expires_at);
+            }
+            None => {
+                acl.expires_at.remove(&change.entity_id);
+            }
+        }
+    }
+}
+
+fn acl_role_members<'a>(acl: &'a mut Acl, acl_role: &str) -> Option<&'a mut Vec<String>> {
+    match acl_role {
+        "Admin" => Some(&mut acl.admin),
+        "Writer" => Some(&mut acl.writer),
+        "Reader" => Some(&mut acl.reader),
+        _ => None,
+    }
+}
@@ -0,0 +1,157 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    entity_profile::{
+        get_latest_entity_profile, list_entity_profiles_for_agent, update_entity_profile,
+        EntityProfileResponse, UpdateEntityProfileInput,
+    },
+    hive_invitation::{get_open_entries, get_open_entry},
+    Signal,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeviceLinkClaimResponse {
+    pub device_link_claim: DeviceLinkClaim,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClaimDeviceLinkInput {
+    pub entity_profile_hash: ActionHash,
+    pub new_device: AgentPubKey,
+}
+
+/// Claims that another device belongs to the same entity as this one and notifies it. Nothing
+/// changes until the new device calls `accept_device_link`.
+#[hdk_extern]
+pub fn claim_device_link(input: ClaimDeviceLinkInput) -> ExternResult<DeviceLinkClaimResponse> {
+    let (_, entity_profile_revision) =
+        get_latest_entity_profile(input.entity_profile_hash.clone())?;
+    let device_link_claim = DeviceLinkClaim {
+        entity_profile_hash: input.entity_profile_hash,
+        entity_profile_revision,
+        new_device: input.new_device.clone(),
+    };
+    let action_hash = create_entry(&EntryTypes::DeviceLinkClaim(device_link_claim.clone()))?;
+    create_link(
+        input.new_device.clone(),
+        action_hash.clone(),
+        LinkTypes::AgentToDeviceLinkClaims,
+        (),
+    )?;
+
+    send_remote_signal(
+        Signal::DeviceLinkClaimed {
+            device_link_claim_hash: action_hash.clone(),
+            device_link_claim: device_link_claim.clone(),
+        },
+        vec![input.new_device],
+    )?;
+
+    Ok(DeviceLinkClaimResponse {
+        device_link_claim,
+        hash: action_hash.to_string(),
+    })
+}
+
+/// Device link claims addressed to this device that are still open.
+#[hdk_extern]
+pub fn list_my_device_link_claims(_: ()) -> ExternResult<Vec<DeviceLinkClaimResponse>> {
+    let links = get_links(
+        agent_info()?.agent_latest_pubkey,
+        LinkTypes::AgentToDeviceLinkClaims,
+        None,
+    )?;
+    Ok(get_open_entries::<DeviceLinkClaim>(links)?
+        .into_iter()
+        .map(|(hash, device_link_claim)| DeviceLinkClaimResponse {
+            device_link_claim,
+            hash: hash.to_string(),
+        })
+        .collect())
+}
+
+/// Accepts a device link claim addressed to this device: commits the acceptance, the profile
+/// revision that adds this device to the agents of the entity and closes the claim in a single
+/// zome call.
+#[hdk_extern]
+pub fn accept_device_link(
+    device_link_claim_hash: ActionHash,
+) -> ExternResult<EntityProfileResponse> {
+    let device_link_claim: DeviceLinkClaim = get_open_entry(device_link_claim_hash.clone())?;
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    if device_link_claim.new_device != my_agent_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The device link claim is addressed to a different device"
+        ))));
+    }
+    let (mut updated_entity_profile, previous_entity_profile_hash) =
+        get_latest_entity_profile(device_link_claim.entity_profile_hash.clone())?;
+    updated_entity_profile.agents.push(my_agent_pub_key);
+
+    create_entry(&EntryTypes::DeviceLinkAcceptance(DeviceLinkAcceptance {
+        device_link_claim_hash: device_link_claim_hash.clone(),
+    }))?;
+    let response = update_entity_profile(UpdateEntityProfileInput {
+        original_entity_profile_hash: device_link_claim.entity_profile_hash,
+        previous_entity_profile_hash,
+        updated_entity_profile,
+    })?;
+    delete_entry(device_link_claim_hash)?;
+    Ok(response)
+}
+
+/// Closes a device link claim without linking, by either of the two devices.
+#[hdk_extern]
+pub fn decline_device_link(device_link_claim_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(device_link_claim_hash)
+}
+
+/// The agent and every device linked to it through the entity profiles it is still listed in.
+/// Validation only lets devices into a profile by accepting a claim.
+#[hdk_extern]
+pub fn get_linked_devices(agent: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let mut devices = vec![agent.clone()];
+    for response in list_entity_profiles_for_agent(agent.clone())? {
+        if !response.entity_profile.agents.contains(&agent) {
+            continue;
+        }
+        for device in response.entity_profile.agents {
+            if !devices.contains(&device) {
+                devices.push(device);
+            }
+        }
+    }
+    Ok(devices)
+}
+
+/// The initial key of the agent that now signs with `agent`, under which its content is indexed.
+/// Entity profiles list the latest keys of their devices.
+#[hdk_extern]
+pub fn get_initial_agent_pub_key(agent: AgentPubKey) -> ExternResult<AgentPubKey> {
+    let links = get_links(agent.clone(), LinkTypes::AgentToInitialKey, None)?;
+    Ok(links
+        .into_iter()
+        .filter(|link| link.author == agent)
+        .find_map(|link| link.target.into_agent_pub_key())
+        .unwrap_or(agent))
+}
+
+/// Links the latest key of this agent to its initial key once it has rotated its key.
+pub fn link_initial_agent_pub_key() -> ExternResult<()> {
+    let agent_info = agent_info()?;
+    if agent_info.agent_latest_pubkey == agent_info.agent_initial_pubkey
+        || get_initial_agent_pub_key(agent_info.agent_latest_pubkey.clone())?
+            == agent_info.agent_initial_pubkey
+    {
+        return Ok(());
+    }
+    create_link(
+        agent_info.agent_latest_pubkey,
+        agent_info.agent_initial_pubkey,
+        LinkTypes::AgentToInitialKey,
+        (),
+    )?;
+    Ok(())
+}
@@ -3,9 +3,23 @@ use hdk::{hash_path::path::Component, prelude::*};
 use zome_utils::*;
 
 use crate::{
-    dynamic_links::create_dynamic_links, hive_link::create_hive_link,
-    humm_content_id_link::create_humm_content_id_link, linking::acl_links::create_acl_links,
+    acl_inheritance::{
+        get_effective_acl, get_effective_public_key_acl, get_inheriting_content_hashes,
+    },
+    device_link::{get_initial_agent_pub_key, get_linked_devices, link_initial_agent_pub_key},
+    dynamic_links::create_dynamic_links,
+    entity_profile::attach_author_entity_profile,
+    group::{principal_includes_entity, resolve_entity_principals},
+    hive::{attach_hive_membership, get_latest_hive_revision},
+    hive_link::{create_hive_link, get_hive_content_types},
+    humm_content_id_link::create_humm_content_id_link,
+    key_envelope::{commit_key_envelope, WrappedKey},
+    linking::acl_links::{
+        acl_role_link_type, create_acl_inheritance_link, create_acl_links, get_acl_parent_targets,
+        update_acl_links,
+    },
     time_indexed_links::*,
+    Signal,
 };
 
 #[hdk_entry_helper]
@@ -26,14 +40,26 @@ pub struct CreateEncryptedContentInput {
     pub bytes: SerializedBytes,
     pub acl: Acl,
     pub public_key_acl: Acl,
+    #[serde(default)]
+    pub inherits_from: Option<AclParent>,
+    #[serde(default)]
+    pub hive_membership: Option<ActionHash>,
+    #[serde(default)]
+    pub author_entity_profile: Option<ActionHash>,
     pub dynamic_links: Option<Vec<String>>,
+    #[serde(default)]
+    pub key_envelopes: Vec<WrappedKey>,
+    /// Indexes the content at this time instead of the time of the action, for imports of
+    /// historical content. Validation bounds how far it may lie from the action.
+    #[serde(default)]
+    pub index_time: Option<Timestamp>,
 }
 
 #[hdk_extern]
 pub fn create_encrypted_content(
     input: CreateEncryptedContentInput,
 ) -> ExternResult<EncryptedContentResponse> {
-    let encrypted_content = EncryptedContent {
+    let mut encrypted_content = EncryptedContent {
         header: EncryptedContentHeader {
             id: input.id,
             hive_id: input.hive_id.clone(),
@@ -41,9 +67,29 @@ pub fn create_encrypted_content(
             revision_author_signing_public_key: input.revision_author_signing_public_key,
             acl: input.acl,
             public_key_acl: input.public_key_acl,
+            inherits_from: input.inherits_from,
+            hive_membership: input.hive_membership,
+            author_entity_profile: input.author_entity_profile,
+            author_group: None,
+            key_epoch: 0,
         },
         bytes: input.bytes,
     };
+    match encrypted_content.header.hive_membership.clone() {
+        None => attach_hive_membership(&mut encrypted_content.header)?,
+        // validation cannot find removals in revisions the author never cited, so only the latest
+        // revision proves the membership
+        Some(hive_membership) => {
+            if let Some(latest) = get_latest_hive_revision(&encrypted_content.header.hive_id)? {
+                if hive_membership != latest {
+                    return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                        "The cited Hive revision is not the latest one"
+                    ))));
+                }
+            }
+        }
+    }
+    attach_author_entity_profile(&mut encrypted_content.header)?;
     let action_hash = create_entry(&EntryTypes::EncryptedContent(encrypted_content.clone()))?;
     // let record = get(action_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
     //     WasmErrorInner::Guest(String::from(
@@ -59,8 +105,8 @@ pub fn create_encrypted_content(
         (),
     )?;
 
-    // create link to the author
-    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    // create link to the author, keyed on the initial key so that it survives key rotation
+    let my_agent_pub_key = agent_info()?.agent_initial_pubkey;
     let author_link_path = Path::from(vec![
         Component::from(my_agent_pub_key.to_string()),
         Component::from(input.content_type),
@@ -71,11 +117,14 @@ pub fn create_encrypted_content(
         LinkTypes::Hive,
         (),
     )?;
+    link_initial_agent_pub_key()?;
 
     // acl links
     create_acl_links(encrypted_content.clone(), action_hash.clone())?;
+    create_acl_inheritance_link(&encrypted_content.header, action_hash.clone())?;
 
-    // hive link - ignore empty hive_id which is used by hive discovery entries
+    // hive link - ignore empty hive_id which is used by legacy hive discovery entries, hives
+    // with a `Hive` entry are listed through the hive directory instead
     if input.hive_id != "" {
         create_hive_link(encrypted_content.clone(), action_hash.clone())?;
     }
@@ -93,7 +142,43 @@ pub fn create_encrypted_content(
     }
 
     // time indexing links
-    time_index_encrypted_content(action_hash.clone(), &encrypted_content.header.content_type)?;
+    time_index_encrypted_content(
+        action_hash.clone(),
+        &encrypted_content.header.content_type,
+        input.index_time,
+    )?;
+    if input.hive_id != "" {
+        time_index_hive_content(
+            action_hash.clone(),
+            &input.hive_id,
+            &encrypted_content.header.content_type,
+            input.index_time,
+        )?;
+        time_index_hive_change(
+            &input.hive_id,
+            action_hash.clone(),
+            action_hash.clone(),
+            "Create",
+        )?;
+    }
+    time_index_modification(
+        action_hash.clone(),
+        action_hash.clone(),
+        &input.hive_id,
+        &encrypted_content.header.content_type,
+    )?;
+
+    // content key wrapped for each reader
+    for wrapped_key in input.key_envelopes {
+        commit_key_envelope(action_hash.clone(), action_hash.clone(), wrapped_key)?;
+    }
+
+    emit_signal(Signal::ContentCreated {
+        hive_id: encrypted_content.header.hive_id.clone(),
+        content_type: encrypted_content.header.content_type.clone(),
+        id: encrypted_content.header.id.clone(),
+        hash: action_hash.clone(),
+    })?;
 
     Ok(EncryptedContentResponse {
         encrypted_content,
@@ -104,12 +189,7 @@ pub fn create_encrypted_content(
 
 #[hdk_extern]
 pub fn get_encrypted_content(content_hash: ActionHash) -> ExternResult<EncryptedContentResponse> {
-    let ah = get_eh(content_hash.clone())?;
-    let Some((entry, hash, _)) = get_latest_typed_from_eh(ah)? else {
-        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Could not find the EncryptedContent"
-        ))));
-    };
+    let (entry, hash) = get_latest_encrypted_content(content_hash.clone())?;
     Ok(EncryptedContentResponse {
         encrypted_content: entry,
         hash: hash.to_string(),
@@ -117,6 +197,42 @@ pub fn get_encrypted_content(content_hash: ActionHash) -> ExternResult<Encrypted
     })
 }
 
+pub fn get_latest_encrypted_content(
+    content_hash: ActionHash,
+) -> ExternResult<(EncryptedContent, ActionHash)> {
+    let ah = get_eh(content_hash)?;
+    let Some((entry, hash, _)) = get_latest_typed_from_eh(ah)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the EncryptedContent"
+        ))));
+    };
+    Ok((entry, hash))
+}
+
+/// A specific revision of an `EncryptedContent`, as opposed to the latest one.
+pub fn get_encrypted_content_revision(action_hash: ActionHash) -> ExternResult<EncryptedContent> {
+    let record = get(action_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the EncryptedContent"))
+    ))?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the EncryptedContent"
+        ))))
+}
+
+pub fn get_original_encrypted_content_hash(content_hash: ActionHash) -> ExternResult<ActionHash> {
+    let original_hash_link = get_links(content_hash, LinkTypes::OriginalHashPointer, None)?;
+    original_hash_link
+        .first()
+        .and_then(|link| link.target.clone().into_action_hash())
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the hash of the original EncryptedContent"
+        ))))
+}
+
 #[hdk_extern]
 pub fn get_many_encrypted_content(
     ahs: Vec<ActionHash>,
@@ -136,12 +252,54 @@ pub struct GetEncryptedContentByTimeAndAuthorInput {
     limit: Option<usize>,
 }
 
+/// Content of one type by the author, or by any device linked to it, within a time range, latest
+/// first. Content is indexed under the initial public key of each device, `author` can be either
+/// key.
 #[hdk_extern]
 pub fn get_encrypted_content_by_time_and_author(
     input: GetEncryptedContentByTimeAndAuthorInput,
 ) -> ExternResult<Vec<EncryptedContentResponse>> {
-    let res = get_encrypted_content_time_index_links(
-        input.author,
+    let mut links: Vec<(Timestamp, Link)> = vec![];
+    for device in get_linked_devices(input.author)? {
+        let res = get_encrypted_content_time_index_links(
+            get_initial_agent_pub_key(device)?,
+            &input.content_type,
+            input.start_time,
+            input.end_time,
+            input.limit,
+        )?;
+        links.extend(res.1);
+    }
+    links.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|(_, link)| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    hashes.truncate(input.limit.unwrap_or(usize::MAX));
+    get_many_encrypted_content(hashes)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEncryptedContentByTimeAndHiveInput {
+    hive_id: String,
+    content_type: String,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+    limit: Option<usize>,
+}
+
+/// Content of one type in a hive within a time range, latest first.
+#[hdk_extern]
+pub fn get_encrypted_content_by_time_and_hive(
+    input: GetEncryptedContentByTimeAndHiveInput,
+) -> ExternResult<Vec<EncryptedContentResponse>> {
+    let res = get_hive_content_time_index_links(
+        &input.hive_id,
         &input.content_type,
         input.start_time,
         input.end_time,
@@ -203,6 +361,11 @@ pub fn list_by_hive_link(input: ListByHiveInput) -> ExternResult<Vec<EncryptedCo
     get_many_encrypted_content(hashes)
 }
 
+#[hdk_extern]
+pub fn list_hive_content_types(hive_id: String) -> ExternResult<Vec<String>> {
+    get_hive_content_types(hive_id)
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ListByContentIdInput {
     pub hive_id: String,
@@ -251,31 +414,91 @@ pub struct ListByAclInput {
 //     Reader,
 // }
 
+/// Lists content where the entity holds the role, directly or through one of its groups, and
+/// content that inherits the role from a hive policy or another content of the hive.
 #[hdk_extern]
 pub fn list_by_acl_link(input: ListByAclInput) -> ExternResult<Vec<EncryptedContentResponse>> {
-    let path = Path::from(vec![
-        Component::from(input.hive_id),
-        Component::from(input.content_type),
-        Component::from(input.entity_id.clone()),
-    ]);
-    let links = match input.acl_role.as_str() {
-        "Owner" => get_links(path.path_entry_hash()?, LinkTypes::HummContentOwner, None)?,
-        "Admin" => get_links(path.path_entry_hash()?, LinkTypes::HummContentAdmin, None)?,
-        "Writer" => get_links(path.path_entry_hash()?, LinkTypes::HummContentWriter, None)?,
-        "Reader" => get_links(path.path_entry_hash()?, LinkTypes::HummContentReader, None)?,
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                "Invalid acl_role"
-            ))))
+    let link_type = acl_role_link_type(&input.acl_role)?;
+    let now = sys_time()?;
+    let principals = resolve_entity_principals(&input.entity_id)?;
+    let mut hashes =
+        get_acl_link_targets(&input.hive_id, &input.content_type, link_type, &principals)?;
+
+    // content of any type and hive policies pass their grants on to inheriting content
+    let parents = get_acl_parent_targets(&input.hive_id, link_type, &principals)?;
+    for hash in get_inheriting_content_hashes(parents, &input.content_type)? {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
         }
-    };
+    }
 
-    let hashes: Vec<ActionHash> = links
-        .into_iter()
-        .map(|link| link.target.into_action_hash())
-        .filter_map(|x| x)
-        .collect();
-    get_many_encrypted_content(hashes)
+    // the links don't know about expiry, keep the content whose effective ACL still grants the
+    // role to one of the principals
+    let mut encrypted_content = vec![];
+    for response in get_many_encrypted_content(hashes)? {
+        let acl = get_effective_acl(&response.encrypted_content.header)?;
+        if acl_role_principals(&acl, &input.acl_role)
+            .iter()
+            .any(|principal| principals.contains(principal) && !acl.is_expired(principal, now))
+        {
+            encrypted_content.push(response);
+        }
+    }
+    Ok(encrypted_content)
+}
+
+/// The original hashes of the content whose ACL links grant one of the principals the role.
+fn get_acl_link_targets(
+    hive_id: &str,
+    content_type: &str,
+    link_type: LinkTypes,
+    principals: &[String],
+) -> ExternResult<Vec<ActionHash>> {
+    let mut hashes: Vec<ActionHash> = vec![];
+    for principal in principals {
+        let path = Path::from(vec![
+            Component::from(hive_id.to_string()),
+            Component::from(content_type.to_string()),
+            Component::from(principal.clone()),
+        ]);
+        let links = get_links(path.path_entry_hash()?, link_type, None)?;
+        for hash in links
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+        {
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckAclPermissionInput {
+    pub content_hash: ActionHash,
+    pub acl_role: String,
+    pub entity_id: String,
+}
+
+/// Whether the entity holds the role on the latest revision of the content, resolving group
+/// principals to their current members and inherited ACLs to their latest revisions. Expired
+/// grants are ignored.
+#[hdk_extern]
+pub fn check_acl_permission(input: CheckAclPermissionInput) -> ExternResult<bool> {
+    acl_role_link_type(&input.acl_role)?;
+    let (encrypted_content, _) = get_latest_encrypted_content(input.content_hash)?;
+    let acl = get_effective_acl(&encrypted_content.header)?;
+    let now = sys_time()?;
+    for principal in acl_role_principals(&acl, &input.acl_role) {
+        if acl.is_expired(&principal, now) {
+            continue;
+        }
+        if principal_includes_entity(&principal, &input.entity_id)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -283,21 +506,58 @@ pub struct ListByAuthorInput {
     pub author: String,
     pub content_type: String,
 }
+/// Lists content created by the author or by any device linked to it. Content is listed under
+/// the initial public key of each device, `author` can be either key.
 #[hdk_extern]
 pub fn list_by_author(input: ListByAuthorInput) -> ExternResult<Vec<EncryptedContentResponse>> {
-    let path = Path::from(vec![
-        Component::from(input.author),
-        Component::from(input.content_type),
-    ]);
+    let authors = match AgentPubKeyB64::from_b64_str(&input.author) {
+        Ok(agent) => get_linked_devices(AgentPubKey::from(agent))?
+            .into_iter()
+            .map(|device| Ok(get_initial_agent_pub_key(device)?.to_string()))
+            .collect::<ExternResult<Vec<String>>>()?,
+        Err(_) => vec![input.author],
+    };
+    let mut hashes: Vec<ActionHash> = vec![];
+    for author in authors {
+        let path = Path::from(vec![
+            Component::from(author),
+            Component::from(input.content_type.clone()),
+        ]);
+        let links = get_links(path.path_entry_hash()?, LinkTypes::Hive, None)?;
+        for hash in links
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+        {
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
+            }
+        }
+    }
+    get_many_encrypted_content(hashes)
+}
 
-    let links = get_links(path.path_entry_hash()?, LinkTypes::Hive, None)?;
-    let hashes: Vec<ActionHash> = links
-        .into_iter()
-        .map(|link| link.target.into_action_hash())
-        .filter_map(|x| x)
-        .collect();
-    let a = get_many_encrypted_content(hashes);
-    a
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckPublicKeyAclPermissionInput {
+    pub content_hash: ActionHash,
+    pub acl_role: String,
+    pub agent: AgentPubKey,
+}
+
+/// Whether the agent, or any device linked to it, holds the role in the effective public key ACL
+/// of the latest revision of the content. Expired grants are ignored.
+#[hdk_extern]
+pub fn check_public_key_acl_permission(
+    input: CheckPublicKeyAclPermissionInput,
+) -> ExternResult<bool> {
+    acl_role_link_type(&input.acl_role)?;
+    let (encrypted_content, _) = get_latest_encrypted_content(input.content_hash)?;
+    let public_key_acl = get_effective_public_key_acl(&encrypted_content.header)?;
+    let now = sys_time()?;
+    let principals = acl_role_principals(&public_key_acl, &input.acl_role);
+    Ok(get_linked_devices(input.agent)?.iter().any(|device| {
+        let device = device.to_string();
+        principals.contains(&device) && !public_key_acl.is_expired(&device, now)
+    }))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -306,54 +566,118 @@ pub struct UpdateEncryptedContentInput {
     pub updated_encrypted_content: EncryptedContent,
 }
 
+/// Commits a new revision of an `EncryptedContent`. The ACL links are moved from the ACL of the
+/// latest revision, which they reflect, even if the update is based on an older revision.
 #[hdk_extern]
 pub fn update_encrypted_content(
-    input: UpdateEncryptedContentInput,
+    mut input: UpdateEncryptedContentInput,
 ) -> ExternResult<EncryptedContentResponse> {
-    let updated_encrypted_content_hash = update_entry(
-        input.previous_encrypted_content_hash.clone(),
+    let original_hash =
+        get_original_encrypted_content_hash(input.previous_encrypted_content_hash.clone())?;
+    let (latest_encrypted_content, _) = get_latest_encrypted_content(original_hash)?;
+    let (updated_encrypted_content_hash, original_hash) = commit_encrypted_content_update(
+        input.previous_encrypted_content_hash,
+        &mut input.updated_encrypted_content,
+    )?;
+    update_acl_links(
+        &latest_encrypted_content,
         &input.updated_encrypted_content,
+        original_hash,
     )?;
-    let original_hash_link = get_links(
-        input.previous_encrypted_content_hash.clone(),
-        LinkTypes::OriginalHashPointer,
-        None,
+
+    let record = get_encrypted_content(updated_encrypted_content_hash.clone())?;
+    Ok(record)
+}
+
+/// Commits a new revision of an `EncryptedContent` along with its revision graph links, citing
+/// the current hive membership and entity profile of the author. Returns the hash of the new
+/// revision and the hash of the original content.
+pub fn commit_encrypted_content_update(
+    previous_encrypted_content_hash: ActionHash,
+    updated_encrypted_content: &mut EncryptedContent,
+) -> ExternResult<(ActionHash, ActionHash)> {
+    attach_hive_membership(&mut updated_encrypted_content.header)?;
+    attach_author_entity_profile(&mut updated_encrypted_content.header)?;
+    let updated_encrypted_content_hash = update_entry(
+        previous_encrypted_content_hash.clone(),
+        &*updated_encrypted_content,
     )?;
-    if original_hash_link.is_empty() {
-        return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "Could not find the hash of the original EncryptedContent that is trying to be updated"
-        ))));
-    }
+    let original_hash = get_original_encrypted_content_hash(previous_encrypted_content_hash)?;
     create_link(
-        original_hash_link[0]
-            .clone()
-            .target
-            .into_action_hash()
-            .unwrap(),
+        original_hash.clone(),
         updated_encrypted_content_hash.clone(),
         LinkTypes::EncryptedContentUpdates,
         (),
     )?;
     create_link(
         updated_encrypted_content_hash.clone(),
-        original_hash_link[0]
-            .clone()
-            .target
-            .into_action_hash()
-            .unwrap(),
+        original_hash.clone(),
         LinkTypes::OriginalHashPointer,
         (),
     )?;
-
-    // TODO: create time link. get rid of default links and update links?
-    let record = get_encrypted_content(updated_encrypted_content_hash.clone())?;
-    Ok(record)
+    if updated_encrypted_content.header.hive_id != "" {
+        time_index_hive_change(
+            &updated_encrypted_content.header.hive_id,
+            updated_encrypted_content_hash.clone(),
+            original_hash.clone(),
+            "Update",
+        )?;
+    }
+    time_index_modification(
+        updated_encrypted_content_hash.clone(),
+        original_hash.clone(),
+        &updated_encrypted_content.header.hive_id,
+        &updated_encrypted_content.header.content_type,
+    )?;
+    emit_signal(Signal::ContentUpdated {
+        hive_id: updated_encrypted_content.header.hive_id.clone(),
+        content_type: updated_encrypted_content.header.content_type.clone(),
+        id: updated_encrypted_content.header.id.clone(),
+        hash: updated_encrypted_content_hash.clone(),
+        original_hash: original_hash.clone(),
+    })?;
+    Ok((updated_encrypted_content_hash, original_hash))
 }
 
 #[hdk_extern]
 pub fn delete_encrypted_content(
     original_encrypted_content_hash: ActionHash,
 ) -> ExternResult<ActionHash> {
-    delete_entry(original_encrypted_content_hash)
+    let delete_hash = delete_entry(original_encrypted_content_hash.clone())?;
     // TODO: delete links
+    // the hive change is recorded and signaled when the original is at hand, the delete doesn't
+    // wait for it
+    match get_original_header(original_encrypted_content_hash.clone()) {
+        Ok(Some(header)) => {
+            if header.hive_id != "" {
+                time_index_hive_change(
+                    &header.hive_id,
+                    delete_hash.clone(),
+                    original_encrypted_content_hash.clone(),
+                    "Delete",
+                )?;
+            }
+            emit_signal(Signal::ContentDeleted {
+                hive_id: header.hive_id,
+                content_type: header.content_type,
+                id: header.id,
+                hash: original_encrypted_content_hash,
+            })?;
+        }
+        Ok(None) => (),
+        Err(err) => error!("Error recording the hive change of a delete: {:?}", err),
+    }
+    Ok(delete_hash)
+}
+
+/// The header of the original revision, from the local source chain when it's there.
+fn get_original_header(
+    original_encrypted_content_hash: ActionHash,
+) -> ExternResult<Option<EncryptedContentHeader>> {
+    let Some(record) = get(original_encrypted_content_hash, GetOptions::content())? else {
+        return Ok(None);
+    };
+    let encrypted_content: Option<EncryptedContent> =
+        record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+    Ok(encrypted_content.map(|encrypted_content| encrypted_content.header))
 }
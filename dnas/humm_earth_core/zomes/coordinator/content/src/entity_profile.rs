@@ -0,0 +1,336 @@
+use content_integrity::*;
+use hdk::prelude::*;
+use zome_utils::*;
+
+use crate::{
+    encrypted_content::{get_encrypted_content_revision, get_original_encrypted_content_hash},
+    group::get_group_revision_for_entity,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EntityProfileResponse {
+    pub entity_profile: EntityProfile,
+    pub hash: String,
+    pub original_hash: String,
+}
+
+#[hdk_extern]
+pub fn create_entity_profile(entity_profile: EntityProfile) -> ExternResult<EntityProfileResponse> {
+    let action_hash = create_entry(&EntryTypes::EntityProfile(entity_profile.clone()))?;
+    create_link(
+        entity_directory_path_hash(&entity_profile.entity_id)?,
+        action_hash.clone(),
+        LinkTypes::EntityDirectory,
+        (),
+    )?;
+    for agent in entity_profile.agents.iter() {
+        create_link(
+            agent.clone(),
+            action_hash.clone(),
+            LinkTypes::AgentToEntityProfiles,
+            (),
+        )?;
+    }
+    Ok(EntityProfileResponse {
+        entity_profile,
+        hash: action_hash.to_string(),
+        original_hash: action_hash.to_string(),
+    })
+}
+
+/// The profile of an entity ID. If several profiles claim the same ID the earliest one wins.
+#[hdk_extern]
+pub fn get_entity_profile(entity_id: String) -> ExternResult<Option<EntityProfileResponse>> {
+    let mut links = get_links(
+        entity_directory_path_hash(&entity_id)?,
+        LinkTypes::EntityDirectory,
+        None,
+    )?;
+    links.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.target.to_string().cmp(&b.target.to_string()))
+    });
+    let Some(original_hash) = links
+        .into_iter()
+        .find_map(|link| link.target.into_action_hash())
+    else {
+        return Ok(None);
+    };
+    Ok(Some(get_entity_profile_by_hash(original_hash)?))
+}
+
+/// The agent keys allowed to act for an entity, empty if the entity has no profile.
+#[hdk_extern]
+pub fn get_entity_agents(entity_id: String) -> ExternResult<Vec<AgentPubKey>> {
+    Ok(get_entity_profile(entity_id)?
+        .map(|response| response.entity_profile.agents)
+        .unwrap_or_default())
+}
+
+#[hdk_extern]
+pub fn get_entity_profile_by_hash(
+    original_entity_profile_hash: ActionHash,
+) -> ExternResult<EntityProfileResponse> {
+    let (entity_profile, hash) = get_latest_entity_profile(original_entity_profile_hash.clone())?;
+    Ok(EntityProfileResponse {
+        entity_profile,
+        hash: hash.to_string(),
+        original_hash: original_entity_profile_hash.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateEntityProfileInput {
+    pub original_entity_profile_hash: ActionHash,
+    pub previous_entity_profile_hash: ActionHash,
+    pub updated_entity_profile: EntityProfile,
+}
+
+/// Updates the latest revision of a profile, `previous_entity_profile_hash` must be that revision.
+#[hdk_extern]
+pub fn update_entity_profile(
+    input: UpdateEntityProfileInput,
+) -> ExternResult<EntityProfileResponse> {
+    let (_, latest_entity_profile_hash) =
+        get_latest_entity_profile(input.original_entity_profile_hash.clone())?;
+    if latest_entity_profile_hash != input.previous_entity_profile_hash {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The previous EntityProfile is not the latest revision"
+        ))));
+    }
+    let previous_record = get(
+        input.previous_entity_profile_hash.clone(),
+        GetOptions::default(),
+    )?
+    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+        "Could not find the previous EntityProfile"
+    ))))?;
+    let previous_entity_profile: EntityProfile = previous_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the previous EntityProfile"
+        ))))?;
+
+    let updated_entity_profile_hash = update_entry(
+        input.previous_entity_profile_hash,
+        &input.updated_entity_profile,
+    )?;
+    create_link(
+        input.original_entity_profile_hash.clone(),
+        updated_entity_profile_hash.clone(),
+        LinkTypes::EntityProfileUpdates,
+        (),
+    )?;
+
+    for agent in previous_entity_profile.agents.iter() {
+        if !input.updated_entity_profile.agents.contains(agent) {
+            let links = get_links(agent.clone(), LinkTypes::AgentToEntityProfiles, None)?;
+            for link in links {
+                if link.target == AnyLinkableHash::from(input.original_entity_profile_hash.clone())
+                {
+                    delete_link(link.create_link_hash)?;
+                }
+            }
+        }
+    }
+    for agent in input.updated_entity_profile.agents.iter() {
+        if !previous_entity_profile.agents.contains(agent) {
+            create_link(
+                agent.clone(),
+                input.original_entity_profile_hash.clone(),
+                LinkTypes::AgentToEntityProfiles,
+                (),
+            )?;
+        }
+    }
+
+    Ok(EntityProfileResponse {
+        entity_profile: input.updated_entity_profile,
+        hash: updated_entity_profile_hash.to_string(),
+        original_hash: input.original_entity_profile_hash.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeDeviceInput {
+    pub entity_profile_hash: ActionHash,
+    pub device: AgentPubKey,
+    pub revoked_at: Option<Timestamp>, // `None` revokes from now on
+}
+
+/// Removes a device from the agents of an entity and records its revocation. Content the device
+/// signs from `revoked_at` on while citing this or a later profile revision is invalid.
+#[hdk_extern]
+pub fn revoke_device(input: RevokeDeviceInput) -> ExternResult<EntityProfileResponse> {
+    let (mut updated_entity_profile, previous_entity_profile_hash) =
+        get_latest_entity_profile(input.entity_profile_hash.clone())?;
+    if !updated_entity_profile.agents.contains(&input.device) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The device does not act for the entity"
+        ))));
+    }
+    updated_entity_profile
+        .agents
+        .retain(|agent| agent != &input.device);
+    updated_entity_profile.revoked_agents.push(RevokedAgent {
+        agent: input.device,
+        revoked_at: input.revoked_at.unwrap_or(sys_time()?),
+    });
+    update_entity_profile(UpdateEntityProfileInput {
+        original_entity_profile_hash: input.entity_profile_hash,
+        previous_entity_profile_hash,
+        updated_entity_profile,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokedDeviceContentResponse {
+    pub encrypted_content: EncryptedContent,
+    pub hash: String,
+    pub original_hash: String,
+    pub authored_at: Timestamp,
+    pub after_revocation: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListContentByRevokedDeviceInput {
+    pub entity_profile_hash: ActionHash,
+    pub device: AgentPubKey,
+}
+
+/// Every `EncryptedContent` revision signed by a revoked device of an entity, oldest first, so
+/// that it can be reviewed. Revisions signed from the revocation on are flagged.
+#[hdk_extern]
+pub fn list_content_by_revoked_device(
+    input: ListContentByRevokedDeviceInput,
+) -> ExternResult<Vec<RevokedDeviceContentResponse>> {
+    let (entity_profile, _) = get_latest_entity_profile(input.entity_profile_hash)?;
+    let Some(revoked_at) = entity_profile
+        .revoked_agents
+        .iter()
+        .find(|revoked| revoked.agent == input.device)
+        .map(|revoked| revoked.revoked_at)
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The device is not revoked for the entity"
+        ))));
+    };
+
+    let filter = ChainQueryFilter::new().entry_type(UnitEntryTypes::EncryptedContent.try_into()?);
+    let activity = get_agent_activity(input.device, filter, ActivityRequest::Full)?;
+    let mut responses: Vec<RevokedDeviceContentResponse> = vec![];
+    for (_, hash) in activity.valid_activity {
+        let Some(record) = get(hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let authored_at = record.action().timestamp();
+        let original_hash = match record.action() {
+            Action::Update(_) => get_original_encrypted_content_hash(hash.clone())?,
+            _ => hash.clone(),
+        };
+        responses.push(RevokedDeviceContentResponse {
+            encrypted_content: get_encrypted_content_revision(hash.clone())?,
+            hash: hash.to_string(),
+            original_hash: original_hash.to_string(),
+            authored_at,
+            after_revocation: authored_at >= revoked_at,
+        });
+    }
+    Ok(responses)
+}
+
+/// The profiles of the entities an agent acts for.
+#[hdk_extern]
+pub fn list_entity_profiles_for_agent(
+    agent: AgentPubKey,
+) -> ExternResult<Vec<EntityProfileResponse>> {
+    get_entity_profile_hashes_for_agent(agent)?
+        .into_iter()
+        .map(get_entity_profile_by_hash)
+        .collect()
+}
+
+pub fn get_latest_entity_profile(
+    original_entity_profile_hash: ActionHash,
+) -> ExternResult<(EntityProfile, ActionHash)> {
+    let eh = get_eh(original_entity_profile_hash)?;
+    let Some((entity_profile, hash, _)) = get_latest_typed_from_eh(eh)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the EntityProfile"
+        ))));
+    };
+    Ok((entity_profile, hash))
+}
+
+/// Points a header that cites an entity profile at the latest profile revision through which the
+/// calling agent acts for the content: the entity cited so far, the owner, a writer or a member of
+/// a writer group, in that order. Entities that only write through a group also cite the latest
+/// revision of that group. If the agent acts for none of them the latest revision of the cited
+/// profile is cited anyway, so that a revoked device never cites a revision from before its
+/// revocation. Headers that don't cite a profile are left untouched.
+pub fn attach_author_entity_profile(header: &mut EncryptedContentHeader) -> ExternResult<()> {
+    let Some(cited_revision) = header.author_entity_profile.clone() else {
+        return Ok(());
+    };
+    let (cited_profile, cited_latest_revision) =
+        get_latest_entity_profile(original_action_hash(cited_revision)?)?;
+    header.author_entity_profile = Some(cited_latest_revision);
+    header.author_group = None;
+    let mut my_profiles: Vec<(EntityProfile, ActionHash)> = vec![];
+    for original_hash in get_entity_profile_hashes_for_agent(agent_info()?.agent_latest_pubkey)? {
+        my_profiles.push(get_latest_entity_profile(original_hash)?);
+    }
+    let writers = acl_role_principals(&header.acl, "Writer");
+    let preferred_profile = my_profiles
+        .iter()
+        .find(|(profile, _)| profile.entity_id == cited_profile.entity_id)
+        .or_else(|| {
+            my_profiles
+                .iter()
+                .find(|(profile, _)| profile.entity_id == header.acl.owner)
+        })
+        .or_else(|| {
+            my_profiles
+                .iter()
+                .find(|(profile, _)| writers.contains(&profile.entity_id))
+        });
+    let preferred_profile = match preferred_profile {
+        Some(profile) => Some(profile),
+        None => {
+            let mut group_member_profile = None;
+            for profile in my_profiles.iter() {
+                if get_group_revision_for_entity(&writers, &profile.0.entity_id)?.is_some() {
+                    group_member_profile = Some(profile);
+                    break;
+                }
+            }
+            group_member_profile
+        }
+    };
+    if let Some((profile, hash)) = preferred_profile {
+        header.author_entity_profile = Some(hash.clone());
+        header.author_group =
+            match profile.entity_id == header.acl.owner || writers.contains(&profile.entity_id) {
+                true => None,
+                false => get_group_revision_for_entity(&writers, &profile.entity_id)?,
+            };
+    }
+    Ok(())
+}
+
+fn get_entity_profile_hashes_for_agent(agent: AgentPubKey) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(agent, LinkTypes::AgentToEntityProfiles, None)?;
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    Ok(hashes)
+}
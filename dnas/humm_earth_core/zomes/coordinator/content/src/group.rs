@@ -0,0 +1,212 @@
+use content_integrity::*;
+use hdk::{hash_path::path::Component, prelude::*};
+use zome_utils::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupResponse {
+    pub group: Group,
+    pub hash: String,
+    pub original_hash: String,
+    pub principal: String,
+}
+
+#[hdk_extern]
+pub fn create_group(group: Group) -> ExternResult<GroupResponse> {
+    let action_hash = create_entry(&EntryTypes::Group(group.clone()))?;
+    for member in group.members.iter() {
+        create_group_member_link(member, action_hash.clone())?;
+    }
+    Ok(GroupResponse {
+        group,
+        hash: action_hash.to_string(),
+        original_hash: action_hash.to_string(),
+        principal: group_principal(&action_hash),
+    })
+}
+
+#[hdk_extern]
+pub fn get_group(original_group_hash: ActionHash) -> ExternResult<GroupResponse> {
+    let (group, hash) = get_latest_group(original_group_hash.clone())?;
+    Ok(GroupResponse {
+        group,
+        hash: hash.to_string(),
+        original_hash: original_group_hash.to_string(),
+        principal: group_principal(&original_group_hash),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateGroupInput {
+    pub original_group_hash: ActionHash,
+    pub previous_group_hash: ActionHash,
+    pub updated_group: Group,
+}
+
+/// Updates a group and its membership links. Every ACL that lists the group principal follows
+/// the new membership without being rewritten.
+#[hdk_extern]
+pub fn update_group(input: UpdateGroupInput) -> ExternResult<GroupResponse> {
+    let previous_record =
+        get(input.previous_group_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from("Could not find the previous Group"))
+        ))?;
+    let previous_group: Group = previous_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the previous Group"
+        ))))?;
+
+    let updated_group_hash = update_entry(input.previous_group_hash, &input.updated_group)?;
+    create_link(
+        input.original_group_hash.clone(),
+        updated_group_hash.clone(),
+        LinkTypes::GroupUpdates,
+        (),
+    )?;
+
+    for member in previous_group.members.iter() {
+        if !input.updated_group.members.contains(member) {
+            delete_group_member_link(member, input.original_group_hash.clone())?;
+        }
+    }
+    for member in input.updated_group.members.iter() {
+        if !previous_group.members.contains(member) {
+            create_group_member_link(member, input.original_group_hash.clone())?;
+        }
+    }
+
+    Ok(GroupResponse {
+        group: input.updated_group,
+        hash: updated_group_hash.to_string(),
+        original_hash: input.original_group_hash.to_string(),
+        principal: group_principal(&input.original_group_hash),
+    })
+}
+
+#[hdk_extern]
+pub fn delete_group(original_group_hash: ActionHash) -> ExternResult<ActionHash> {
+    let (group, _) = get_latest_group(original_group_hash.clone())?;
+    for member in group.members.iter() {
+        delete_group_member_link(member, original_group_hash.clone())?;
+    }
+    delete_entry(original_group_hash)
+}
+
+/// The groups an entity is currently a member of.
+#[hdk_extern]
+pub fn list_groups_for_entity(entity_id: String) -> ExternResult<Vec<GroupResponse>> {
+    get_group_hashes_for_entity(&entity_id)?
+        .into_iter()
+        .map(get_group)
+        .collect()
+}
+
+/// Every principal an entity acts as in an ACL: its own ID and the principals of its groups.
+pub fn resolve_entity_principals(entity_id: &str) -> ExternResult<Vec<String>> {
+    let mut principals = vec![entity_id.to_string()];
+    principals.extend(
+        get_group_hashes_for_entity(entity_id)?
+            .iter()
+            .map(group_principal),
+    );
+    Ok(principals)
+}
+
+/// Whether an ACL principal designates the entity, directly or through the latest revision of a
+/// group the entity owns or is a member of.
+pub fn principal_includes_entity(principal: &str, entity_id: &str) -> ExternResult<bool> {
+    if principal == entity_id {
+        return Ok(true);
+    }
+    match group_principal_hash(principal) {
+        Some(original_group_hash) => {
+            let (group, _) = get_latest_group(original_group_hash)?;
+            Ok(group.includes(entity_id))
+        }
+        None => Ok(false),
+    }
+}
+
+/// The latest revision of the first group among the principals that includes the entity.
+pub fn get_group_revision_for_entity(
+    principals: &[String],
+    entity_id: &str,
+) -> ExternResult<Option<ActionHash>> {
+    for principal in principals {
+        let Some(original_group_hash) = group_principal_hash(principal) else {
+            continue;
+        };
+        let (group, hash) = get_latest_group(original_group_hash)?;
+        if group.includes(entity_id) {
+            return Ok(Some(hash));
+        }
+    }
+    Ok(None)
+}
+
+pub fn get_latest_group(original_group_hash: ActionHash) -> ExternResult<(Group, ActionHash)> {
+    let eh = get_eh(original_group_hash)?;
+    let Some((group, hash, _)) = get_latest_typed_from_eh(eh)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the Group"
+        ))));
+    };
+    Ok((group, hash))
+}
+
+/// The groups whose latest revision lists the entity. The membership links only narrow down the
+/// candidates.
+fn get_group_hashes_for_entity(entity_id: &str) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(
+        group_membership_path(entity_id).path_entry_hash()?,
+        LinkTypes::GroupMember,
+        None,
+    )?;
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if hashes.contains(&hash) || !principal_includes_entity(&group_principal(&hash), entity_id)?
+        {
+            continue;
+        }
+        hashes.push(hash);
+    }
+    Ok(hashes)
+}
+
+fn create_group_member_link(
+    member: &str,
+    original_group_hash: ActionHash,
+) -> ExternResult<ActionHash> {
+    create_link(
+        group_membership_path(member).path_entry_hash()?,
+        original_group_hash,
+        LinkTypes::GroupMember,
+        (),
+    )
+}
+
+fn delete_group_member_link(member: &str, original_group_hash: ActionHash) -> ExternResult<()> {
+    let links = get_links(
+        group_membership_path(member).path_entry_hash()?,
+        LinkTypes::GroupMember,
+        None,
+    )?;
+    for link in links {
+        if link.target == AnyLinkableHash::from(original_group_hash.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn group_membership_path(entity_id: &str) -> Path {
+    Path::from(vec![
+        Component::from(GROUP_MEMBERSHIP_INDEX),
+        Component::from(entity_id.to_string()),
+    ])
+}
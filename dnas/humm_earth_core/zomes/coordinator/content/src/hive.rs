@@ -0,0 +1,241 @@
+use content_integrity::*;
+use hdk::prelude::*;
+use zome_utils::*;
+
+use crate::hive_directory::update_hive_directory_links;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveResponse {
+    pub hive: Hive,
+    pub hash: String,
+    pub original_hash: String,
+    pub hive_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateHiveInput {
+    pub name: String,
+    pub admins: Vec<AgentPubKey>,
+    pub members: Vec<AgentPubKey>,
+    pub membership_policy: MembershipPolicy,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub visibility: HiveVisibility,
+}
+
+/// Creates a hive owned by the calling agent. The original hash of the hive is its `hive_id`.
+#[hdk_extern]
+pub fn create_hive(input: CreateHiveInput) -> ExternResult<HiveResponse> {
+    let hive = Hive {
+        name: input.name,
+        owner: agent_info()?.agent_latest_pubkey,
+        admins: input.admins,
+        members: input.members,
+        membership_policy: input.membership_policy,
+        description: input.description,
+        visibility: input.visibility,
+        removals: vec![],
+        revoked_invitations: vec![],
+    };
+    let action_hash = create_entry(&EntryTypes::Hive(hive.clone()))?;
+    for agent in hive.agents() {
+        create_hive_member_link(&agent, action_hash.clone(), action_hash.clone())?;
+    }
+    update_hive_directory_links(action_hash.clone(), None, &hive)?;
+    Ok(HiveResponse {
+        hive,
+        hash: action_hash.to_string(),
+        original_hash: action_hash.to_string(),
+        hive_id: action_hash.to_string(),
+    })
+}
+
+#[hdk_extern]
+pub fn get_hive(original_hive_hash: ActionHash) -> ExternResult<HiveResponse> {
+    let (hive, hash) = get_latest_hive(original_hive_hash.clone())?;
+    Ok(HiveResponse {
+        hive,
+        hash: hash.to_string(),
+        original_hash: original_hive_hash.to_string(),
+        hive_id: original_hive_hash.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateHiveInput {
+    pub original_hive_hash: ActionHash,
+    pub previous_hive_hash: ActionHash,
+    pub updated_hive: Hive,
+}
+
+#[hdk_extern]
+pub fn update_hive(input: UpdateHiveInput) -> ExternResult<HiveResponse> {
+    let previous_record =
+        get(input.previous_hive_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from("Could not find the previous Hive"))
+        ))?;
+    let previous_hive: Hive = previous_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the previous Hive"
+        ))))?;
+    commit_hive_update(
+        input.original_hive_hash,
+        input.previous_hive_hash,
+        &previous_hive,
+        input.updated_hive,
+    )
+}
+
+/// Adds the calling agent to the members of a hive with an `Open` membership policy.
+#[hdk_extern]
+pub fn join_hive(original_hive_hash: ActionHash) -> ExternResult<HiveResponse> {
+    let (hive, hash) = get_latest_hive(original_hive_hash.clone())?;
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    if hive.is_member(&my_agent_pub_key) {
+        return get_hive(original_hive_hash);
+    }
+    let mut updated_hive = hive.clone();
+    updated_hive.members.push(my_agent_pub_key);
+    commit_hive_update(original_hive_hash, hash, &hive, updated_hive)
+}
+
+/// The hives an agent owns, administers or is a member of.
+#[hdk_extern]
+pub fn list_hives_for_agent(agent: AgentPubKey) -> ExternResult<Vec<HiveResponse>> {
+    let links = get_links(
+        hive_membership_path_hash(&agent)?,
+        LinkTypes::HiveMember,
+        None,
+    )?;
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    hashes.into_iter().map(get_hive).collect()
+}
+
+#[hdk_extern]
+pub fn list_my_hives(_: ()) -> ExternResult<Vec<HiveResponse>> {
+    list_hives_for_agent(agent_info()?.agent_latest_pubkey)
+}
+
+pub fn get_latest_hive(original_hive_hash: ActionHash) -> ExternResult<(Hive, ActionHash)> {
+    let eh = get_eh(original_hive_hash)?;
+    let Some((hive, hash, _)) = get_latest_typed_from_eh(eh)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the Hive"
+        ))));
+    };
+    Ok((hive, hash))
+}
+
+/// Cites the latest revision of the hive as the membership proof of a header whose `hive_id`
+/// refers to a `Hive`. Legacy string hives are left untouched.
+pub fn attach_hive_membership(header: &mut EncryptedContentHeader) -> ExternResult<()> {
+    if let Some(hash) = get_latest_hive_revision(&header.hive_id)? {
+        header.hive_membership = Some(hash);
+    }
+    Ok(())
+}
+
+/// The latest revision of the `Hive` a hive ID refers to, `None` for legacy string hives.
+pub fn get_latest_hive_revision(hive_id: &str) -> ExternResult<Option<ActionHash>> {
+    let Some(original_hive_hash) = hive_action_hash(hive_id) else {
+        return Ok(None);
+    };
+    let (_, hash) = get_latest_hive(original_hive_hash)?;
+    Ok(Some(hash))
+}
+
+/// Commits a new hive revision, recording the removal of every agent that leaves the hive, and
+/// brings the membership links in line with it.
+pub fn commit_hive_update(
+    original_hive_hash: ActionHash,
+    previous_hive_hash: ActionHash,
+    previous_hive: &Hive,
+    mut updated_hive: Hive,
+) -> ExternResult<HiveResponse> {
+    let previous_agents = previous_hive.agents();
+    let updated_agents = updated_hive.agents();
+    let now = sys_time()?;
+    for agent in previous_agents.iter() {
+        if !updated_agents.contains(agent) {
+            updated_hive.removals.push(HiveRemoval {
+                agent: agent.clone(),
+                removed_at: now,
+            });
+        }
+    }
+
+    let updated_hive_hash = update_entry(previous_hive_hash, &updated_hive)?;
+    create_link(
+        original_hive_hash.clone(),
+        updated_hive_hash.clone(),
+        LinkTypes::HiveUpdates,
+        (),
+    )?;
+
+    for agent in previous_agents.iter() {
+        if !updated_agents.contains(agent) {
+            delete_hive_member_link(agent, original_hive_hash.clone())?;
+        }
+    }
+    for agent in updated_agents.iter() {
+        if !previous_agents.contains(agent) {
+            create_hive_member_link(agent, original_hive_hash.clone(), updated_hive_hash.clone())?;
+        }
+    }
+    update_hive_directory_links(
+        original_hive_hash.clone(),
+        Some(previous_hive),
+        &updated_hive,
+    )?;
+
+    Ok(HiveResponse {
+        hive: updated_hive,
+        hash: updated_hive_hash.to_string(),
+        original_hash: original_hive_hash.to_string(),
+        hive_id: original_hive_hash.to_string(),
+    })
+}
+
+fn create_hive_member_link(
+    agent: &AgentPubKey,
+    original_hive_hash: ActionHash,
+    hive_revision: ActionHash,
+) -> ExternResult<ActionHash> {
+    let tag =
+        SerializedBytes::try_from(HiveMemberTag { hive_revision }).map_err(|e| wasm_error!(e))?;
+    create_link(
+        hive_membership_path_hash(agent)?,
+        original_hive_hash,
+        LinkTypes::HiveMember,
+        LinkTag::new(tag.bytes().clone()),
+    )
+}
+
+fn delete_hive_member_link(
+    agent: &AgentPubKey,
+    original_hive_hash: ActionHash,
+) -> ExternResult<()> {
+    let links = get_links(
+        hive_membership_path_hash(agent)?,
+        LinkTypes::HiveMember,
+        None,
+    )?;
+    for link in links {
+        if link.target == AnyLinkableHash::from(original_hive_hash.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    encrypted_content::{
+        commit_encrypted_content_update, create_encrypted_content, get_encrypted_content_revision,
+        CreateEncryptedContentInput,
+    },
+    linking::{
+        acl_links::update_acl_links,
+        dynamic_links::get_dynamic_link_values,
+        hive_link::{get_hive_content_hashes, get_hive_content_types},
+    },
+};
+
+const HIVE_ARCHIVE_FORMAT: &str = "humm-hive-archive";
+const HIVE_ARCHIVE_VERSION: u32 = 1;
+const DEFAULT_IMPORT_BATCH_SIZE: usize = 20;
+
+/// A portable copy of the content of a hive. `format` and `version` identify the layout, the
+/// archive is serialized as MessagePack with named fields.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct HiveArchive {
+    pub format: String,
+    pub version: u32,
+    pub hive_id: String,
+    pub exported_at: Timestamp,
+    pub items: Vec<HiveArchiveItem>,
+}
+
+/// One content with all its revisions, oldest first. The ACLs travel in the revision headers.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveArchiveItem {
+    pub original_hash: ActionHash,
+    pub dynamic_links: Vec<String>,
+    pub revisions: Vec<HiveArchiveRevision>,
+}
+
+/// A revision and the revision it updates, `None` for the original content.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveArchiveRevision {
+    pub hash: ActionHash,
+    pub previous_hash: Option<ActionHash>,
+    pub authored_at: Timestamp,
+    pub encrypted_content: EncryptedContent,
+}
+
+/// Exports every content of a hive that has not been deleted, with its revisions, revision graph
+/// and dynamic link values. Key envelopes are not exported, readers need new ones after an
+/// import.
+#[hdk_extern]
+pub fn export_hive(hive_id: String) -> ExternResult<SerializedBytes> {
+    let mut items: Vec<HiveArchiveItem> = vec![];
+    for content_type in get_hive_content_types(hive_id.clone())? {
+        for original_hash in get_hive_content_hashes(hive_id.clone(), content_type)? {
+            if let Some(item) = export_hive_item(original_hash)? {
+                items.push(item);
+            }
+        }
+    }
+    SerializedBytes::try_from(HiveArchive {
+        format: String::from(HIVE_ARCHIVE_FORMAT),
+        version: HIVE_ARCHIVE_VERSION,
+        hive_id,
+        exported_at: sys_time()?,
+        items,
+    })
+    .map_err(|e| wasm_error!(e))
+}
+
+fn export_hive_item(original_hash: ActionHash) -> ExternResult<Option<HiveArchiveItem>> {
+    let Some(Details::Record(details)) = get_details(original_hash.clone(), GetOptions::default())?
+    else {
+        return Ok(None);
+    };
+    if !details.deletes.is_empty() {
+        return Ok(None);
+    }
+    let Some(original) = archive_revision(details.record)? else {
+        return Ok(None);
+    };
+    let mut revisions = vec![original];
+
+    let links = get_links(
+        original_hash.clone(),
+        LinkTypes::EncryptedContentUpdates,
+        None,
+    )?;
+    for action_hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if revisions
+            .iter()
+            .any(|revision| revision.hash == action_hash)
+        {
+            continue;
+        }
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        if let Some(revision) = archive_revision(record)? {
+            revisions.push(revision);
+        }
+    }
+    revisions.sort_by(|a, b| {
+        a.authored_at
+            .cmp(&b.authored_at)
+            .then_with(|| a.hash.to_string().cmp(&b.hash.to_string()))
+    });
+
+    Ok(Some(HiveArchiveItem {
+        dynamic_links: get_dynamic_link_values(original_hash.clone())?,
+        original_hash,
+        revisions,
+    }))
+}
+
+fn archive_revision(record: Record) -> ExternResult<Option<HiveArchiveRevision>> {
+    let previous_hash = match record.action() {
+        Action::Create(_) => None,
+        Action::Update(update) => Some(update.original_action_address.clone()),
+        _ => return Ok(None),
+    };
+    let encrypted_content: Option<EncryptedContent> =
+        record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+    Ok(
+        encrypted_content.map(|encrypted_content| HiveArchiveRevision {
+            hash: record.action_address().clone(),
+            previous_hash,
+            authored_at: record.action().timestamp(),
+            encrypted_content,
+        }),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportHiveInput {
+    pub archive: SerializedBytes,
+    /// The `Hive` revision listing the calling agent, cited by the imported content of a `Hive`.
+    #[serde(default)]
+    pub hive_membership: Option<ActionHash>,
+    pub cursor: Option<usize>,
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportHiveItemReport {
+    pub original_hash: String, // the hash in the archive
+    pub imported_hash: String,
+    pub revision_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportHiveResponse {
+    pub items: Vec<ImportHiveItemReport>,
+    pub total: usize,
+    pub next_cursor: Option<usize>,
+}
+
+/// Recreates one batch of an `export_hive` archive under the calling agent, keeping the original
+/// creation times. Call again with `next_cursor` until it is `None`. Any failure fails the batch.
+#[hdk_extern]
+pub fn import_hive(input: ImportHiveInput) -> ExternResult<ImportHiveResponse> {
+    let archive = HiveArchive::try_from(input.archive).map_err(|e| wasm_error!(e))?;
+    if archive.format != HIVE_ARCHIVE_FORMAT || archive.version != HIVE_ARCHIVE_VERSION {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Unsupported hive archive: \"{}\" version {}",
+            archive.format, archive.version
+        ))));
+    }
+
+    let start = input.cursor.unwrap_or(0).min(archive.items.len());
+    let end = start
+        .saturating_add(input.batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE))
+        .min(archive.items.len());
+    let mut items: Vec<ImportHiveItemReport> = vec![];
+    for item in archive.items[start..end].iter() {
+        let imported_hash = import_hive_item(item, input.hive_membership.clone()).map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Could not import {}: {:?}",
+                item.original_hash, e
+            )))
+        })?;
+        items.push(ImportHiveItemReport {
+            original_hash: item.original_hash.to_string(),
+            imported_hash: imported_hash.to_string(),
+            revision_count: item.revisions.len(),
+        });
+    }
+
+    Ok(ImportHiveResponse {
+        items,
+        total: archive.items.len(),
+        next_cursor: (end < archive.items.len()).then_some(end),
+    })
+}
+
+/// Commits the revisions of an archived item in order, mapping each archived revision to its
+/// imported counterpart. Returns the hash of the imported original content.
+fn import_hive_item(
+    item: &HiveArchiveItem,
+    hive_membership: Option<ActionHash>,
+) -> ExternResult<ActionHash> {
+    let Some(original) = item
+        .revisions
+        .iter()
+        .find(|revision| revision.previous_hash.is_none())
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The archived content has no original revision"
+        ))));
+    };
+    let header = original.encrypted_content.header.clone();
+    let response = create_encrypted_content(CreateEncryptedContentInput {
+        id: header.id,
+        hive_id: header.hive_id,
+        content_type: header.content_type,
+        revision_author_signing_public_key: header.revision_author_signing_public_key,
+        bytes: original.encrypted_content.bytes.clone(),
+        acl: header.acl,
+        public_key_acl: header.public_key_acl,
+        inherits_from: header.inherits_from,
+        hive_membership: hive_membership.clone(),
+        author_entity_profile: None,
+        dynamic_links: Some(item.dynamic_links.clone()),
+        key_envelopes: vec![],
+        index_time: Some(original.authored_at),
+    })?;
+    let imported_original_hash = ActionHash::from(
+        ActionHashB64::from_b64_str(&response.original_hash)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?,
+    );
+
+    // (archived hash, imported hash)
+    let mut imported: Vec<(ActionHash, ActionHash)> =
+        vec![(original.hash.clone(), imported_original_hash.clone())];
+    for revision in item.revisions.iter() {
+        let Some(previous_hash) = revision.previous_hash.clone() else {
+            continue;
+        };
+        let Some((_, imported_previous_hash)) = imported
+            .iter()
+            .find(|(hash, _)| hash == &previous_hash)
+            .cloned()
+        else {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "The archived revision updates a revision that is not in the archive"
+            ))));
+        };
+        let previous_encrypted_content =
+            get_encrypted_content_revision(imported_previous_hash.clone())?;
+        let mut updated_encrypted_content = revision.encrypted_content.clone();
+        updated_encrypted_content.header.hive_membership = hive_membership.clone();
+        updated_encrypted_content.header.author_entity_profile = None;
+        updated_encrypted_content.header.author_group = None;
+        let (updated_hash, _) = commit_encrypted_content_update(
+            imported_previous_hash,
+            &mut updated_encrypted_content,
+        )?;
+        update_acl_links(
+            &previous_encrypted_content,
+            &updated_encrypted_content,
+            imported_original_hash.clone(),
+        )?;
+        imported.push((revision.hash.clone(), updated_hash));
+    }
+    Ok(imported_original_hash)
+}
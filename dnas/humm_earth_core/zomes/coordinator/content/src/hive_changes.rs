@@ -0,0 +1,107 @@
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::time_indexed_links::{get_hive_change_time_index_links, HiveChangeTag};
+
+const DEFAULT_HIVE_CHANGES_PAGE_SIZE: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetHiveChangesSinceInput {
+    pub hive_id: String,
+    pub since: Timestamp,
+    pub cursor: Option<HiveChangesCursor>,
+    pub limit: Option<usize>,
+}
+
+/// The last change of a page. Changes are ordered by time then hash, so a cursor stays valid
+/// when changes are indexed between calls.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HiveChangesCursor {
+    pub timestamp: Timestamp,
+    pub hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveChange {
+    pub operation: String, // "Create", "Update" or "Delete"
+    pub hash: String,
+    pub original_hash: String,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveChangesResponse {
+    pub changes: Vec<HiveChange>,
+    pub next_cursor: Option<HiveChangesCursor>,
+}
+
+/// The creates, updates and deletes of content in a hive from `since` on, oldest first. Call
+/// again with `next_cursor` until it is `None`, then store the timestamp of the last change for
+/// the next sync.
+#[hdk_extern]
+pub fn get_hive_changes_since(
+    input: GetHiveChangesSinceInput,
+) -> ExternResult<HiveChangesResponse> {
+    let since = match &input.cursor {
+        Some(cursor) => cursor.timestamp,
+        None => input.since,
+    };
+    let limit = input.limit.unwrap_or(DEFAULT_HIVE_CHANGES_PAGE_SIZE);
+    let cursor = input
+        .cursor
+        .as_ref()
+        .map(|cursor| (cursor.timestamp, cursor.hash.to_string()));
+    // changes are sorted and compared by the key of the cursor
+    let mut keyed_changes: Vec<((Timestamp, String), ActionHash, HiveChange)> =
+        get_hive_change_time_index_links(&input.hive_id, since)?
+            .into_iter()
+            .filter_map(|(timestamp, link)| hive_change(timestamp, link))
+            .map(|(hash, change)| ((change.timestamp, hash.to_string()), hash, change))
+            .filter(|(key, _, _)| cursor.as_ref().map_or(true, |cursor| key > cursor))
+            .collect();
+    keyed_changes.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+    let mut seen: BTreeSet<ActionHash> = BTreeSet::new();
+    let mut changes: Vec<(ActionHash, HiveChange)> = vec![];
+    for (_, hash, change) in keyed_changes {
+        if changes.len() > limit {
+            break;
+        }
+        if seen.insert(hash.clone()) {
+            changes.push((hash, change));
+        }
+    }
+
+    let has_more = changes.len() > limit;
+    changes.truncate(limit);
+    let next_cursor = match has_more {
+        true => changes.last().map(|(hash, change)| HiveChangesCursor {
+            timestamp: change.timestamp,
+            hash: hash.clone(),
+        }),
+        false => None,
+    };
+    Ok(HiveChangesResponse {
+        changes: changes.into_iter().map(|(_, change)| change).collect(),
+        next_cursor,
+    })
+}
+
+/// Reads a change from its time index link: the target is the action, the item type of the tag
+/// the operation and its data the original content hash.
+fn hive_change(timestamp: Timestamp, link: Link) -> Option<(ActionHash, HiveChange)> {
+    let hash = link.target.into_action_hash()?;
+    let tag = HiveChangeTag::try_from(SerializedBytes::from(UnsafeBytes::from(
+        link.tag.into_inner(),
+    )))
+    .ok()?;
+    let original_hash = ActionHash::from_raw_39(tag.custom_data).ok()?;
+    Some((
+        hash.clone(),
+        HiveChange {
+            operation: tag.item_type,
+            hash: hash.to_string(),
+            original_hash: original_hash.to_string(),
+            timestamp,
+        },
+    ))
+}
@@ -0,0 +1,118 @@
+use hdk::prelude::*;
+
+use crate::{
+    encrypted_content::get_latest_encrypted_content,
+    linking::hive_link::{get_hive_content_hashes, get_hive_content_types},
+};
+
+const DEFAULT_HIVE_DIGEST_BUCKET_SECONDS: i64 = 24 * 60 * 60;
+const HIVE_DIGEST_LENGTH: i32 = 32;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetHiveDigestInput {
+    pub hive_id: String,
+    pub content_type: Option<String>,
+    pub bucket_seconds: Option<i64>,
+}
+
+/// The digest of the content created within `[start, end)`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveDigestBucket {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub digest: String,
+    pub item_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveDigestResponse {
+    pub digest: String,
+    pub item_count: usize,
+    pub buckets: Vec<HiveDigestBucket>,
+}
+
+/// A BLAKE2b digest over the (original hash, latest hash) pairs of the undeleted content of a hive,
+/// plus one digest per `bucket_seconds` of creation time so that clients re-sync only the buckets
+/// that differ.
+#[hdk_extern]
+pub fn get_hive_digest(input: GetHiveDigestInput) -> ExternResult<HiveDigestResponse> {
+    let bucket_seconds = input
+        .bucket_seconds
+        .unwrap_or(DEFAULT_HIVE_DIGEST_BUCKET_SECONDS);
+    if bucket_seconds <= 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The bucket size of a hive digest must be positive"
+        ))));
+    }
+    let bucket_micros = bucket_seconds.saturating_mul(1_000_000);
+
+    let content_types = match input.content_type {
+        Some(content_type) => vec![content_type],
+        None => get_hive_content_types(input.hive_id.clone())?,
+    };
+    // (bucket start, original hash, latest hash)
+    let mut items: Vec<(i64, ActionHash, ActionHash)> = vec![];
+    for content_type in content_types {
+        for original_hash in get_hive_content_hashes(input.hive_id.clone(), content_type)? {
+            let Some(Details::Record(details)) =
+                get_details(original_hash.clone(), GetOptions::default())?
+            else {
+                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "Could not find the EncryptedContent"
+                ))));
+            };
+            // deleted content is left out, any other failure fails the digest
+            if !details.deletes.is_empty() {
+                continue;
+            }
+            let (_, latest_hash) = get_latest_encrypted_content(original_hash.clone())?;
+            let created_at = details.record.action().timestamp().as_micros();
+            items.push((
+                created_at.div_euclid(bucket_micros) * bucket_micros,
+                original_hash,
+                latest_hash,
+            ));
+        }
+    }
+    items.sort_by(|(_, a, _), (_, b, _)| a.get_raw_39().cmp(b.get_raw_39()));
+    items.dedup_by(|(_, a, _), (_, b, _)| a == b);
+
+    let mut bucket_starts: Vec<i64> = items.iter().map(|(start, _, _)| *start).collect();
+    bucket_starts.sort();
+    bucket_starts.dedup();
+    let mut buckets: Vec<HiveDigestBucket> = vec![];
+    for start in bucket_starts {
+        let pairs: Vec<(&ActionHash, &ActionHash)> = items
+            .iter()
+            .filter(|(item_start, _, _)| *item_start == start)
+            .map(|(_, original_hash, latest_hash)| (original_hash, latest_hash))
+            .collect();
+        buckets.push(HiveDigestBucket {
+            start: Timestamp::from_micros(start),
+            end: Timestamp::from_micros(start.saturating_add(bucket_micros)),
+            digest: digest_pairs(&pairs)?,
+            item_count: pairs.len(),
+        });
+    }
+
+    let pairs: Vec<(&ActionHash, &ActionHash)> = items
+        .iter()
+        .map(|(_, original_hash, latest_hash)| (original_hash, latest_hash))
+        .collect();
+    Ok(HiveDigestResponse {
+        digest: digest_pairs(&pairs)?,
+        item_count: pairs.len(),
+        buckets,
+    })
+}
+
+/// The hex encoded BLAKE2b hash of the concatenated raw bytes of each pair.
+fn digest_pairs(pairs: &[(&ActionHash, &ActionHash)]) -> ExternResult<String> {
+    let mut bytes: Vec<u8> = vec![];
+    for (original_hash, latest_hash) in pairs {
+        bytes.extend_from_slice(original_hash.get_raw_39());
+        bytes.extend_from_slice(latest_hash.get_raw_39());
+    }
+    let digest = hash_blake2b(bytes, HIVE_DIGEST_LENGTH)?;
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
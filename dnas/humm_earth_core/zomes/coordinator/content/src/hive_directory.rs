@@ -0,0 +1,180 @@
+use content_integrity::*;
+use hdk::{hash_path::path::Component, prelude::*};
+
+use crate::hive::{get_hive, HiveResponse};
+
+const DEFAULT_HIVE_PAGE_SIZE: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListHivesInput {
+    pub cursor: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListHivesResponse {
+    pub hives: Vec<HiveResponse>,
+    pub next_cursor: Option<usize>,
+}
+
+/// Public hives in the order they were listed. Call again with `next_cursor` until it is `None`.
+#[hdk_extern]
+pub fn list_hives(input: ListHivesInput) -> ExternResult<ListHivesResponse> {
+    let mut links = get_links(
+        hive_directory_path().path_entry_hash()?,
+        LinkTypes::HiveDirectory,
+        None,
+    )?;
+    links.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.target.to_string().cmp(&b.target.to_string()))
+    });
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+
+    let start = input.cursor.unwrap_or(0).min(hashes.len());
+    let end = start
+        .saturating_add(input.limit.unwrap_or(DEFAULT_HIVE_PAGE_SIZE))
+        .min(hashes.len());
+    let hives = hashes[start..end]
+        .iter()
+        .cloned()
+        .map(get_hive)
+        .collect::<ExternResult<Vec<HiveResponse>>>()?;
+    Ok(ListHivesResponse {
+        hives,
+        next_cursor: (end < hashes.len()).then_some(end),
+    })
+}
+
+/// Public hives with a word in their name or description that starts with the prefix. The
+/// prefix needs at least `HIVE_SEARCH_PREFIX_LENGTH` characters.
+#[hdk_extern]
+pub fn search_hives(prefix: String) -> ExternResult<Vec<HiveResponse>> {
+    let prefix = truncate_search_token(&prefix.to_lowercase());
+    let Some(path) = hive_search_path(&prefix) else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Search prefixes need at least {} characters",
+            HIVE_SEARCH_PREFIX_LENGTH
+        ))));
+    };
+    let links = get_links(path.path_entry_hash()?, LinkTypes::HiveSearch, None)?;
+    let mut hashes: Vec<ActionHash> = vec![];
+    for link in links {
+        if !link.tag.0.starts_with(prefix.as_bytes()) {
+            continue;
+        }
+        if let Some(hash) = link.target.into_action_hash() {
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
+            }
+        }
+    }
+    hashes.into_iter().map(get_hive).collect()
+}
+
+/// Brings the directory listing of a hive in line with a new revision: private hives are
+/// unlisted and public hives are indexed by the words of their name and description.
+pub fn update_hive_directory_links(
+    original_hive_hash: ActionHash,
+    previous_hive: Option<&Hive>,
+    updated_hive: &Hive,
+) -> ExternResult<()> {
+    let previous_tokens = previous_hive.and_then(hive_search_tokens);
+    let updated_tokens = hive_search_tokens(updated_hive);
+    if previous_tokens == updated_tokens {
+        return Ok(());
+    }
+
+    if let Some(tokens) = previous_tokens {
+        delete_links_to(
+            hive_directory_path(),
+            LinkTypes::HiveDirectory,
+            &original_hive_hash,
+        )?;
+        for token in tokens {
+            if let Some(path) = hive_search_path(&token) {
+                delete_links_to(path, LinkTypes::HiveSearch, &original_hive_hash)?;
+            }
+        }
+    }
+    if let Some(tokens) = updated_tokens {
+        create_link(
+            hive_directory_path().path_entry_hash()?,
+            original_hive_hash.clone(),
+            LinkTypes::HiveDirectory,
+            (),
+        )?;
+        for token in tokens {
+            if let Some(path) = hive_search_path(&token) {
+                create_link(
+                    path.path_entry_hash()?,
+                    original_hive_hash.clone(),
+                    LinkTypes::HiveSearch,
+                    LinkTag::new(token),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The lowercase words of the name and description of a public hive, `None` for private hives.
+fn hive_search_tokens(hive: &Hive) -> Option<Vec<String>> {
+    if hive.visibility != HiveVisibility::Public {
+        return None;
+    }
+    let mut tokens: Vec<String> = vec![];
+    for token in format!("{} {}", hive.name, hive.description)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .map(truncate_search_token)
+    {
+        if token.chars().count() >= HIVE_SEARCH_PREFIX_LENGTH && !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    Some(tokens)
+}
+
+/// The longest prefix of the word that fits in a search link tag.
+fn truncate_search_token(token: &str) -> String {
+    let mut end = token.len().min(MAX_HIVE_SEARCH_TOKEN_LENGTH);
+    while !token.is_char_boundary(end) {
+        end -= 1;
+    }
+    token[..end].to_string()
+}
+
+fn delete_links_to(base: Path, link_type: LinkTypes, target: &ActionHash) -> ExternResult<()> {
+    for link in get_links(base.path_entry_hash()?, link_type, None)? {
+        if link.target == AnyLinkableHash::from(target.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn hive_directory_path() -> Path {
+    Path::from(vec![Component::from(HIVE_DIRECTORY_ANCHOR)])
+}
+
+/// The search bucket of a word or prefix: its first `HIVE_SEARCH_PREFIX_LENGTH` characters.
+fn hive_search_path(token: &str) -> Option<Path> {
+    if token.chars().count() < HIVE_SEARCH_PREFIX_LENGTH {
+        return None;
+    }
+    let bucket: String = token.chars().take(HIVE_SEARCH_PREFIX_LENGTH).collect();
+    Some(Path::from(vec![
+        Component::from(HIVE_SEARCH_INDEX),
+        Component::from(bucket),
+    ]))
+}
@@ -0,0 +1,284 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    hive::{commit_hive_update, get_latest_hive, HiveResponse},
+    Signal,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvitationResponse {
+    pub invitation: Invitation,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JoinRequestResponse {
+    pub join_request: JoinRequest,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InviteToHiveInput {
+    pub hive_hash: ActionHash,
+    pub invitee: AgentPubKey,
+}
+
+/// Invites an agent to a hive and notifies it with a remote signal. Only owners and admins can
+/// invite.
+#[hdk_extern]
+pub fn invite_to_hive(input: InviteToHiveInput) -> ExternResult<InvitationResponse> {
+    let (_, hive_revision) = get_latest_hive(input.hive_hash.clone())?;
+    let invitation = Invitation {
+        hive_hash: input.hive_hash.clone(),
+        hive_revision,
+        invitee: input.invitee.clone(),
+    };
+    let action_hash = create_entry(&EntryTypes::Invitation(invitation.clone()))?;
+    create_link(
+        input.hive_hash,
+        action_hash.clone(),
+        LinkTypes::HiveInvitations,
+        (),
+    )?;
+    create_link(
+        input.invitee.clone(),
+        action_hash.clone(),
+        LinkTypes::AgentInvitations,
+        (),
+    )?;
+
+    send_remote_signal(
+        Signal::InvitationReceived {
+            invitation_hash: action_hash.clone(),
+            invitation: invitation.clone(),
+        },
+        vec![input.invitee],
+    )?;
+
+    Ok(InvitationResponse {
+        invitation,
+        hash: action_hash.to_string(),
+    })
+}
+
+/// Invitations addressed to this agent that have not been declined or revoked.
+#[hdk_extern]
+pub fn list_my_invitations(_: ()) -> ExternResult<Vec<InvitationResponse>> {
+    let links = get_links(
+        agent_info()?.agent_latest_pubkey,
+        LinkTypes::AgentInvitations,
+        None,
+    )?;
+    Ok(get_open_entries::<Invitation>(links)?
+        .into_iter()
+        .map(|(hash, invitation)| InvitationResponse {
+            invitation,
+            hash: hash.to_string(),
+        })
+        .collect())
+}
+
+#[hdk_extern]
+pub fn list_hive_invitations(hive_hash: ActionHash) -> ExternResult<Vec<InvitationResponse>> {
+    let links = get_links(hive_hash, LinkTypes::HiveInvitations, None)?;
+    Ok(get_open_entries::<Invitation>(links)?
+        .into_iter()
+        .map(|(hash, invitation)| InvitationResponse {
+            invitation,
+            hash: hash.to_string(),
+        })
+        .collect())
+}
+
+/// Accepts an invitation addressed to this agent: commits the acceptance, the hive revision that
+/// adds this agent to the members and closes the invitation in a single zome call.
+#[hdk_extern]
+pub fn accept_invitation(invitation_hash: ActionHash) -> ExternResult<HiveResponse> {
+    let invitation: Invitation = get_open_entry(invitation_hash.clone())?;
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    if invitation.invitee != my_agent_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The invitation is addressed to a different agent"
+        ))));
+    }
+    let (hive, previous_hive_hash) = get_latest_hive(invitation.hive_hash.clone())?;
+    let mut updated_hive = hive.clone();
+    updated_hive.members.push(my_agent_pub_key);
+
+    create_entry(&EntryTypes::InvitationAcceptance(InvitationAcceptance {
+        invitation_hash: invitation_hash.clone(),
+        hive_revision: previous_hive_hash.clone(),
+    }))?;
+    let response = commit_hive_update(
+        invitation.hive_hash,
+        previous_hive_hash,
+        &hive,
+        updated_hive,
+    )?;
+    delete_entry(invitation_hash)?;
+    Ok(response)
+}
+
+#[hdk_extern]
+pub fn decline_invitation(invitation_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(invitation_hash)
+}
+
+/// Revokes an invitation that hasn't been accepted yet and notifies the invitee. The revocation is
+/// recorded in the hive, so that the invitation can no longer be used to join it.
+#[hdk_extern]
+pub fn revoke_invitation(invitation_hash: ActionHash) -> ExternResult<ActionHash> {
+    let invitation: Invitation = get_open_entry(invitation_hash.clone())?;
+    let (hive, previous_hive_hash) = get_latest_hive(invitation.hive_hash.clone())?;
+    let mut updated_hive = hive.clone();
+    updated_hive
+        .revoked_invitations
+        .push(invitation_hash.clone());
+    commit_hive_update(
+        invitation.hive_hash,
+        previous_hive_hash,
+        &hive,
+        updated_hive,
+    )?;
+    let delete_hash = delete_entry(invitation_hash.clone())?;
+    send_remote_signal(
+        Signal::InvitationRevoked { invitation_hash },
+        vec![invitation.invitee],
+    )?;
+    Ok(delete_hash)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestToJoinHiveInput {
+    pub hive_hash: ActionHash,
+    pub message: String,
+}
+
+/// Asks to join a hive with the `RequestToJoin` membership policy and notifies its admins.
+#[hdk_extern]
+pub fn request_to_join_hive(input: RequestToJoinHiveInput) -> ExternResult<JoinRequestResponse> {
+    let (hive, hive_revision) = get_latest_hive(input.hive_hash.clone())?;
+    let join_request = JoinRequest {
+        hive_hash: input.hive_hash.clone(),
+        hive_revision,
+        requester: agent_info()?.agent_latest_pubkey,
+        message: input.message,
+    };
+    let action_hash = create_entry(&EntryTypes::JoinRequest(join_request.clone()))?;
+    create_link(
+        input.hive_hash,
+        action_hash.clone(),
+        LinkTypes::HiveJoinRequests,
+        (),
+    )?;
+
+    let mut admins = vec![hive.owner];
+    admins.extend(hive.admins);
+    send_remote_signal(
+        Signal::JoinRequestReceived {
+            join_request_hash: action_hash.clone(),
+            join_request: join_request.clone(),
+        },
+        admins,
+    )?;
+
+    Ok(JoinRequestResponse {
+        join_request,
+        hash: action_hash.to_string(),
+    })
+}
+
+/// Join requests for a hive that have not been accepted, declined or withdrawn.
+#[hdk_extern]
+pub fn list_join_requests(hive_hash: ActionHash) -> ExternResult<Vec<JoinRequestResponse>> {
+    let links = get_links(hive_hash, LinkTypes::HiveJoinRequests, None)?;
+    Ok(get_open_entries::<JoinRequest>(links)?
+        .into_iter()
+        .map(|(hash, join_request)| JoinRequestResponse {
+            join_request,
+            hash: hash.to_string(),
+        })
+        .collect())
+}
+
+/// Adds the requester to the members of the hive and closes the join request. Only owners and
+/// admins can accept.
+#[hdk_extern]
+pub fn accept_join_request(join_request_hash: ActionHash) -> ExternResult<HiveResponse> {
+    let join_request: JoinRequest = get_open_entry(join_request_hash.clone())?;
+    let (hive, previous_hive_hash) = get_latest_hive(join_request.hive_hash.clone())?;
+    let mut updated_hive = hive.clone();
+    if !hive.is_member(&join_request.requester) {
+        updated_hive.members.push(join_request.requester);
+    }
+    let response = commit_hive_update(
+        join_request.hive_hash,
+        previous_hive_hash,
+        &hive,
+        updated_hive,
+    )?;
+    delete_entry(join_request_hash)?;
+    Ok(response)
+}
+
+/// Closes a join request without adding the requester, either by an admin or by the requester.
+#[hdk_extern]
+pub fn decline_join_request(join_request_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(join_request_hash)
+}
+
+/// The entries behind the links that haven't been deleted.
+pub fn get_open_entries<T>(links: Vec<Link>) -> ExternResult<Vec<(ActionHash, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let mut entries = vec![];
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(Details::Record(details)) =
+            get_details(action_hash.clone(), GetOptions::default())?
+        else {
+            continue;
+        };
+        if !details.deletes.is_empty() {
+            continue;
+        }
+        let Some(entry) = details
+            .record
+            .entry()
+            .to_app_option::<T>()
+            .map_err(|e| wasm_error!(e))?
+        else {
+            continue;
+        };
+        entries.push((action_hash, entry));
+    }
+    Ok(entries)
+}
+
+pub fn get_open_entry<T>(action_hash: ActionHash) -> ExternResult<T>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(Details::Record(details)) = get_details(action_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the entry"
+        ))));
+    };
+    if !details.deletes.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The entry has been deleted"
+        ))));
+    }
+    details
+        .record
+        .entry()
+        .to_app_option::<T>()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the entry"
+        ))))
+}
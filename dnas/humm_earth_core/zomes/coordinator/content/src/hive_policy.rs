@@ -0,0 +1,143 @@
+use content_integrity::*;
+use hdk::{hash_path::path::Component, prelude::*};
+use zome_utils::*;
+
+use crate::{
+    hive::get_latest_hive_revision,
+    linking::acl_links::{
+        create_acl_parent_links, delete_acl_parent_links, update_acl_parent_links,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HivePolicyResponse {
+    pub hive_policy: HivePolicy,
+    pub hash: String,
+    pub original_hash: String,
+}
+
+#[hdk_extern]
+pub fn create_hive_policy(mut hive_policy: HivePolicy) -> ExternResult<HivePolicyResponse> {
+    hive_policy.hive_revision = get_latest_hive_revision(&hive_policy.hive_id)?;
+    let action_hash = create_entry(&EntryTypes::HivePolicy(hive_policy.clone()))?;
+    create_link(
+        hive_policies_path(&hive_policy.hive_id).path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::HivePolicies,
+        (),
+    )?;
+    create_acl_parent_links(&hive_policy.hive_id, &hive_policy.acl, action_hash.clone())?;
+    Ok(HivePolicyResponse {
+        hive_policy,
+        hash: action_hash.to_string(),
+        original_hash: action_hash.to_string(),
+    })
+}
+
+#[hdk_extern]
+pub fn get_hive_policy(original_hive_policy_hash: ActionHash) -> ExternResult<HivePolicyResponse> {
+    let (hive_policy, hash) = get_latest_hive_policy(original_hive_policy_hash.clone())?;
+    Ok(HivePolicyResponse {
+        hive_policy,
+        hash: hash.to_string(),
+        original_hash: original_hive_policy_hash.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateHivePolicyInput {
+    pub original_hive_policy_hash: ActionHash,
+    pub previous_hive_policy_hash: ActionHash,
+    pub updated_hive_policy: HivePolicy,
+}
+
+/// Updates a hive policy. Queries pick up the new grants for all inheriting content at once;
+/// validation keeps using the revision each content was committed against.
+#[hdk_extern]
+pub fn update_hive_policy(mut input: UpdateHivePolicyInput) -> ExternResult<HivePolicyResponse> {
+    let (latest_hive_policy, _) = get_latest_hive_policy(input.original_hive_policy_hash.clone())?;
+    input.updated_hive_policy.hive_revision =
+        get_latest_hive_revision(&input.updated_hive_policy.hive_id)?;
+    let updated_hive_policy_hash =
+        update_entry(input.previous_hive_policy_hash, &input.updated_hive_policy)?;
+    create_link(
+        input.original_hive_policy_hash.clone(),
+        updated_hive_policy_hash.clone(),
+        LinkTypes::HivePolicyUpdates,
+        (),
+    )?;
+    update_acl_parent_links(
+        &input.updated_hive_policy.hive_id,
+        &latest_hive_policy.acl,
+        &input.updated_hive_policy.acl,
+        input.original_hive_policy_hash.clone(),
+    )?;
+    Ok(HivePolicyResponse {
+        hive_policy: input.updated_hive_policy,
+        hash: updated_hive_policy_hash.to_string(),
+        original_hash: input.original_hive_policy_hash.to_string(),
+    })
+}
+
+#[hdk_extern]
+pub fn delete_hive_policy(original_hive_policy_hash: ActionHash) -> ExternResult<ActionHash> {
+    let (hive_policy, _) = get_latest_hive_policy(original_hive_policy_hash.clone())?;
+    let links = get_links(
+        hive_policies_path(&hive_policy.hive_id).path_entry_hash()?,
+        LinkTypes::HivePolicies,
+        None,
+    )?;
+    for link in links {
+        if link.target == AnyLinkableHash::from(original_hive_policy_hash.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    delete_acl_parent_links(
+        &hive_policy.hive_id,
+        &hive_policy.acl,
+        original_hive_policy_hash.clone(),
+    )?;
+    delete_entry(original_hive_policy_hash)
+}
+
+#[hdk_extern]
+pub fn list_hive_policies(hive_id: String) -> ExternResult<Vec<HivePolicyResponse>> {
+    get_hive_policy_hashes(&hive_id)?
+        .into_iter()
+        .map(get_hive_policy)
+        .collect()
+}
+
+pub fn get_latest_hive_policy(
+    original_hive_policy_hash: ActionHash,
+) -> ExternResult<(HivePolicy, ActionHash)> {
+    let eh = get_eh(original_hive_policy_hash)?;
+    let Some((hive_policy, hash, _)) = get_latest_typed_from_eh(eh)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the HivePolicy"
+        ))));
+    };
+    Ok((hive_policy, hash))
+}
+
+pub fn get_hive_policy_hashes(hive_id: &str) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(
+        hive_policies_path(hive_id).path_entry_hash()?,
+        LinkTypes::HivePolicies,
+        None,
+    )?;
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    Ok(hashes)
+}
+
+fn hive_policies_path(hive_id: &str) -> Path {
+    Path::from(vec![Component::from(hive_id.to_string())])
+}
@@ -0,0 +1,82 @@
+use content_integrity::*;
+use hdk::{hash_path::path::Component, prelude::*};
+
+use crate::hive::get_latest_hive;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HiveSubscriptionInput {
+    pub hive_id: String,
+    pub content_type: String,
+}
+
+/// Subscribes the calling agent to the content of one type in a hive. Only members of a `Hive`
+/// can subscribe to it. Subscribers receive a `ContentNotification` for content they can read
+/// or, in a `Hive`, for content whose cited revision lists them.
+#[hdk_extern]
+pub fn subscribe_hive(input: HiveSubscriptionInput) -> ExternResult<ActionHash> {
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    if let Some(original_hive_hash) = hive_action_hash(&input.hive_id) {
+        let (hive, _) = get_latest_hive(original_hive_hash)?;
+        if !hive.is_member(&my_agent_pub_key) {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Only members of the hive can subscribe to it"
+            ))));
+        }
+    }
+    let path = hive_feed_path(input.hive_id, input.content_type);
+    let links = get_links(path.path_entry_hash()?, LinkTypes::HiveSubscribers, None)?;
+    if let Some(link) = links
+        .into_iter()
+        .find(|link| link.target == AnyLinkableHash::from(my_agent_pub_key.clone()))
+    {
+        return Ok(link.create_link_hash);
+    }
+    create_link(
+        path.path_entry_hash()?,
+        my_agent_pub_key,
+        LinkTypes::HiveSubscribers,
+        (),
+    )
+}
+
+#[hdk_extern]
+pub fn unsubscribe_hive(input: HiveSubscriptionInput) -> ExternResult<()> {
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    let path = hive_feed_path(input.hive_id, input.content_type);
+    let links = get_links(path.path_entry_hash()?, LinkTypes::HiveSubscribers, None)?;
+    for link in links {
+        if link.target == AnyLinkableHash::from(my_agent_pub_key.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// The agents subscribed to the content of one type in a hive.
+pub fn get_hive_subscribers(
+    hive_id: String,
+    content_type: String,
+) -> ExternResult<Vec<AgentPubKey>> {
+    let links = get_links(
+        hive_feed_path(hive_id, content_type).path_entry_hash()?,
+        LinkTypes::HiveSubscribers,
+        None,
+    )?;
+    let mut subscribers: Vec<AgentPubKey> = vec![];
+    for subscriber in links
+        .into_iter()
+        .filter_map(|link| link.target.into_agent_pub_key())
+    {
+        if !subscribers.contains(&subscriber) {
+            subscribers.push(subscriber);
+        }
+    }
+    Ok(subscribers)
+}
+
+fn hive_feed_path(hive_id: String, content_type: String) -> Path {
+    Path::from(vec![
+        Component::from(hive_id),
+        Component::from(content_type),
+    ])
+}
@@ -0,0 +1,179 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    acl_inheritance::get_effective_public_key_acl,
+    encrypted_content::{
+        commit_encrypted_content_update, get_encrypted_content, get_encrypted_content_revision,
+        get_latest_encrypted_content, EncryptedContentResponse,
+    },
+    linking::acl_links::update_acl_links,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeyEnvelopeResponse {
+    pub key_envelope: KeyEnvelope,
+    pub hash: String,
+}
+
+/// A content key wrapped for one recipient, as passed by the client.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WrappedKey {
+    pub recipient: AgentPubKey,
+    pub wrapped_key: SerializedBytes,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateKeyEnvelopesInput {
+    pub content_hash: ActionHash,
+    pub wrapped_keys: Vec<WrappedKey>,
+}
+
+/// Publishes the key of the latest revision of a content, wrapped for each recipient.
+#[hdk_extern]
+pub fn create_key_envelopes(
+    input: CreateKeyEnvelopesInput,
+) -> ExternResult<Vec<KeyEnvelopeResponse>> {
+    let (_, content_revision) = get_latest_encrypted_content(input.content_hash.clone())?;
+    input
+        .wrapped_keys
+        .into_iter()
+        .map(|wrapped_key| {
+            commit_key_envelope(
+                input.content_hash.clone(),
+                content_revision.clone(),
+                wrapped_key,
+            )
+        })
+        .collect()
+}
+
+/// The newest envelope addressed to the calling agent for a content, if any.
+#[hdk_extern]
+pub fn get_my_key_envelope(content_hash: ActionHash) -> ExternResult<Option<KeyEnvelopeResponse>> {
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_links(
+        content_hash,
+        LinkTypes::KeyEnvelopes,
+        Some(LinkTag::new(my_agent_pub_key.to_string())),
+    )?;
+    let Some(link) = links.into_iter().max_by_key(|link| link.timestamp) else {
+        return Ok(None);
+    };
+    let Some(action_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    get_key_envelope(action_hash)
+}
+
+/// Every envelope issued for a content, oldest first.
+#[hdk_extern]
+pub fn list_key_envelopes(content_hash: ActionHash) -> ExternResult<Vec<KeyEnvelopeResponse>> {
+    let mut links = get_links(content_hash, LinkTypes::KeyEnvelopes, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let mut responses: Vec<KeyEnvelopeResponse> = vec![];
+    for action_hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if let Some(response) = get_key_envelope(action_hash)? {
+            responses.push(response);
+        }
+    }
+    Ok(responses)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RekeyEncryptedContentInput {
+    pub previous_encrypted_content_hash: ActionHash,
+    pub updated_encrypted_content: EncryptedContent,
+    pub wrapped_keys: Vec<WrappedKey>,
+}
+
+/// Commits a revision whose `bytes` are encrypted with a new content key, typically after readers
+/// were removed from the public key ACL. The key epoch moves forward and the new key is wrapped
+/// for every agent that can still read the content, so every reader must be an agent key.
+#[hdk_extern]
+pub fn rekey_encrypted_content(
+    mut input: RekeyEncryptedContentInput,
+) -> ExternResult<EncryptedContentResponse> {
+    let previous_encrypted_content =
+        get_encrypted_content_revision(input.previous_encrypted_content_hash.clone())?;
+    input.updated_encrypted_content.header.key_epoch =
+        previous_encrypted_content.header.key_epoch + 1;
+
+    let public_key_acl = get_effective_public_key_acl(&input.updated_encrypted_content.header)?;
+    for reader in public_key_readers(&public_key_acl) {
+        let agent = AgentPubKey::from(AgentPubKeyB64::from_b64_str(&reader).map_err(|_| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "The reader {} of the public key ACL is not an agent key",
+                reader
+            )))
+        })?);
+        if !input
+            .wrapped_keys
+            .iter()
+            .any(|wrapped_key| wrapped_key.recipient == agent)
+        {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Missing a wrapped key for the reader {}",
+                reader
+            ))));
+        }
+    }
+
+    let (updated_encrypted_content_hash, original_hash) = commit_encrypted_content_update(
+        input.previous_encrypted_content_hash,
+        &mut input.updated_encrypted_content,
+    )?;
+    update_acl_links(
+        &previous_encrypted_content,
+        &input.updated_encrypted_content,
+        original_hash.clone(),
+    )?;
+    for wrapped_key in input.wrapped_keys {
+        commit_key_envelope(
+            original_hash.clone(),
+            updated_encrypted_content_hash.clone(),
+            wrapped_key,
+        )?;
+    }
+    get_encrypted_content(updated_encrypted_content_hash)
+}
+
+/// Commits an envelope for a content revision and links it from the original content, tagged
+/// with the recipient.
+pub fn commit_key_envelope(
+    original_content_hash: ActionHash,
+    content_revision: ActionHash,
+    wrapped_key: WrappedKey,
+) -> ExternResult<KeyEnvelopeResponse> {
+    let key_envelope = KeyEnvelope {
+        content_hash: content_revision,
+        recipient: wrapped_key.recipient,
+        wrapped_key: wrapped_key.wrapped_key,
+    };
+    let action_hash = create_entry(&EntryTypes::KeyEnvelope(key_envelope.clone()))?;
+    create_link(
+        original_content_hash,
+        action_hash.clone(),
+        LinkTypes::KeyEnvelopes,
+        LinkTag::new(key_envelope.recipient.to_string()),
+    )?;
+    Ok(KeyEnvelopeResponse {
+        key_envelope,
+        hash: action_hash.to_string(),
+    })
+}
+
+fn get_key_envelope(action_hash: ActionHash) -> ExternResult<Option<KeyEnvelopeResponse>> {
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let key_envelope: Option<KeyEnvelope> =
+        record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+    Ok(key_envelope.map(|key_envelope| KeyEnvelopeResponse {
+        key_envelope,
+        hash: action_hash.to_string(),
+    }))
+}
@@ -1,13 +1,136 @@
+pub mod acl_inheritance;
+pub mod bulk_acl;
+pub mod device_link;
 pub mod encrypted_content;
+pub mod entity_profile;
+pub mod group;
+pub mod hive;
+pub mod hive_archive;
+pub mod hive_changes;
+pub mod hive_digest;
+pub mod hive_directory;
+pub mod hive_invitation;
+pub mod hive_policy;
+pub mod hive_subscription;
+pub mod key_envelope;
 pub mod linking;
+pub mod notifications;
+pub mod ownership_transfer;
+pub mod recently_modified;
+pub mod signal_preferences;
+
+use std::collections::BTreeSet;
 
 use content_integrity::*;
 use hdk::prelude::*;
 pub use linking::*;
+
+use crate::{
+    bulk_acl::AclRoleEntity,
+    notifications::notify_content_change,
+    signal_preferences::{get_signal_mode, SignalMode},
+};
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    // let any agent deliver remote signals to this cell
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: String::from("remote_signals"),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
     Ok(InitCallbackResult::Pass)
 }
+/// Re-emits remote signals to the UI. The records a signal announces are committed in the same call
+/// that sends it and may not be published yet, so the sender is checked against records that
+/// already existed: invitations must come from an admin of the cited hive revision, revocations
+/// from the inviter, join requests from the requester and device link claims from an agent of the
+/// cited entity profile revision. Content notifications only come from their author, who must be
+/// a member of its `Hive`.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal
+        .decode()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    let is_authentic = match &signal {
+        Signal::InvitationReceived { invitation, .. } => is_sent_by_inviter(invitation)?,
+        Signal::InvitationRevoked { invitation_hash } => {
+            match get_typed_record::<Invitation>(invitation_hash)? {
+                Some((record, invitation)) => {
+                    record.action().author() == &call_info()?.provenance
+                        && is_me(&invitation.invitee)?
+                }
+                None => false,
+            }
+        }
+        Signal::JoinRequestReceived { join_request, .. } => {
+            join_request.requester == call_info()?.provenance
+                && original_action_hash(join_request.hive_revision.clone())?
+                    == join_request.hive_hash
+        }
+        Signal::DeviceLinkClaimed {
+            device_link_claim, ..
+        } => is_sent_by_entity_agent(device_link_claim)?,
+        Signal::ContentNotification { author, header, .. } => {
+            is_sent_by_hive_member(author, &header.hive_id)?
+        }
+        _ => false,
+    };
+    if is_authentic {
+        emit_signal(signal)?;
+    }
+    Ok(())
+}
+fn is_sent_by_inviter(invitation: &Invitation) -> ExternResult<bool> {
+    if !is_me(&invitation.invitee)?
+        || original_action_hash(invitation.hive_revision.clone())? != invitation.hive_hash
+    {
+        return Ok(false);
+    }
+    let provenance = call_info()?.provenance;
+    Ok(get_typed_record::<Hive>(&invitation.hive_revision)?
+        .map_or(false, |(_, hive)| hive.is_admin(&provenance)))
+}
+fn is_sent_by_entity_agent(device_link_claim: &DeviceLinkClaim) -> ExternResult<bool> {
+    if !is_me(&device_link_claim.new_device)?
+        || original_action_hash(device_link_claim.entity_profile_revision.clone())?
+            != device_link_claim.entity_profile_hash
+    {
+        return Ok(false);
+    }
+    let provenance = call_info()?.provenance;
+    Ok(
+        get_typed_record::<EntityProfile>(&device_link_claim.entity_profile_revision)?
+            .map_or(false, |(_, entity_profile)| {
+                entity_profile.agents.contains(&provenance)
+            }),
+    )
+}
+fn is_me(agent: &AgentPubKey) -> ExternResult<bool> {
+    let agent_info = agent_info()?;
+    Ok(agent == &agent_info.agent_initial_pubkey || agent == &agent_info.agent_latest_pubkey)
+}
+fn get_typed_record<T>(action_hash: &ActionHash) -> ExternResult<Option<(Record, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let entry: Option<T> = record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+    Ok(entry.map(|entry| (record, entry)))
+}
+fn is_sent_by_hive_member(author: &AgentPubKey, hive_id: &str) -> ExternResult<bool> {
+    if &call_info()?.provenance != author {
+        return Ok(false);
+    }
+    let Some(original_hive_hash) = hive_action_hash(hive_id) else {
+        return Ok(true);
+    };
+    let (hive, _) = hive::get_latest_hive(original_hive_hash)?;
+    Ok(hive.is_member(author))
+}
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type")]
 pub enum Signal {
@@ -17,7 +140,7 @@ pub enum Signal {
     },
     LinkDeleted {
         action: SignedActionHashed,
-        link_type: LinkTypes,
+        link_type: Option<LinkTypes>,
     },
     EntryCreated {
         action: SignedActionHashed,
@@ -26,22 +149,142 @@ pub enum Signal {
     EntryUpdated {
         action: SignedActionHashed,
         app_entry: EntryTypes,
-        original_app_entry: EntryTypes,
+        original_app_entry: Option<EntryTypes>,
     },
     EntryDeleted {
         action: SignedActionHashed,
-        original_app_entry: EntryTypes,
+        original_app_entry: Option<EntryTypes>,
+    },
+    InvitationReceived {
+        invitation_hash: ActionHash,
+        invitation: Invitation,
+    },
+    InvitationRevoked {
+        invitation_hash: ActionHash,
+    },
+    JoinRequestReceived {
+        join_request_hash: ActionHash,
+        join_request: JoinRequest,
+    },
+    DeviceLinkClaimed {
+        device_link_claim_hash: ActionHash,
+        device_link_claim: DeviceLinkClaim,
+    },
+    ContentNotification {
+        content_hash: ActionHash,
+        original_hash: ActionHash,
+        author: AgentPubKey,
+        header: EncryptedContentHeader,
+    },
+    EntryCreatedSummary {
+        action: SignedActionHashed,
+        summary: EncryptedContentSummary,
+    },
+    EntryUpdatedSummary {
+        action: SignedActionHashed,
+        summary: EncryptedContentSummary,
+    },
+    EntryDeletedSummary {
+        action: SignedActionHashed,
+        original_summary: EncryptedContentSummary,
     },
+    ContentCreated {
+        hive_id: String,
+        content_type: String,
+        id: String,
+        hash: ActionHash,
+    },
+    ContentUpdated {
+        hive_id: String,
+        content_type: String,
+        id: String,
+        hash: ActionHash,
+        original_hash: ActionHash,
+    },
+    ContentDeleted {
+        hive_id: String,
+        content_type: String,
+        id: String,
+        hash: ActionHash,
+    },
+    AclChanged {
+        original_hash: ActionHash,
+        added: Vec<AclRoleEntity>,
+        removed: Vec<AclRoleEntity>,
+    },
+}
+/// An `EncryptedContent` without its `bytes`, sent instead of the entry in `HeaderOnly` mode.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncryptedContentSummary {
+    pub header: EncryptedContentHeader,
+    pub bytes_length: usize,
+}
+impl From<&EncryptedContent> for EncryptedContentSummary {
+    fn from(encrypted_content: &EncryptedContent) -> Self {
+        EncryptedContentSummary {
+            header: encrypted_content.header.clone(),
+            bytes_length: encrypted_content.bytes.bytes().len(),
+        }
+    }
 }
+/// Signals are built from the committed actions and the local source chain only. Data that isn't
+/// on the local chain is `None`.
 #[hdk_extern(infallible)]
 pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
+    let signal_mode = get_signal_mode(()).unwrap_or_default();
+    for action in committed_actions.iter() {
+        if let Err(err) = notify_committed_content(action, &committed_actions) {
+            error!("Error notifying content change: {:?}", err);
+        }
+    }
     for action in committed_actions {
-        if let Err(err) = signal_action(action) {
+        if let Err(err) = signal_action(action, &signal_mode) {
             error!("Error signaling new action: {:?}", err);
         }
     }
 }
-fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
+/// Notifies the agents interested in a committed `EncryptedContent` revision. The typed content
+/// signals are emitted by the externs, which hold both the previous and the new header.
+fn notify_committed_content(
+    action: &SignedActionHashed,
+    committed_actions: &[SignedActionHashed],
+) -> ExternResult<()> {
+    let hash = action.hashed.hash.clone();
+    let original_hash = match action.hashed.content {
+        Action::Create(_) => hash.clone(),
+        // the `OriginalHashPointer` is committed along with every revision
+        Action::Update(_) => match committed_original_hash(&hash, committed_actions) {
+            Some(original_hash) => original_hash,
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let Some(EntryTypes::EncryptedContent(encrypted_content)) = get_entry_for_action(&hash)? else {
+        return Ok(());
+    };
+    notify_content_change(hash, original_hash, &encrypted_content.header)
+}
+/// The target of the `OriginalHashPointer` committed from a revision.
+fn committed_original_hash(
+    revision_hash: &ActionHash,
+    committed_actions: &[SignedActionHashed],
+) -> Option<ActionHash> {
+    committed_actions
+        .iter()
+        .find_map(|action| match action.action() {
+            Action::CreateLink(create_link)
+                if create_link.base_address == revision_hash.clone().into()
+                    && matches!(
+                        LinkTypes::from_type(create_link.zome_index, create_link.link_type),
+                        Ok(Some(LinkTypes::OriginalHashPointer))
+                    ) =>
+            {
+                create_link.target_address.clone().into_action_hash()
+            }
+            _ => None,
+        })
+}
+fn signal_action(action: SignedActionHashed, signal_mode: &SignalMode) -> ExternResult<()> {
     match action.hashed.content.clone() {
         Action::CreateLink(create_link) => {
             if let Ok(Some(link_type)) =
@@ -52,65 +295,91 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
             Ok(())
         }
         Action::DeleteLink(delete_link) => {
-            let record = get(delete_link.link_add_address.clone(), GetOptions::default())?.ok_or(
-                wasm_error!(WasmErrorInner::Guest(
-                    "Failed to fetch CreateLink action".to_string()
-                )),
-            )?;
-            match record.action() {
-                Action::CreateLink(create_link) => {
-                    if let Ok(Some(link_type)) =
-                        LinkTypes::from_type(create_link.zome_index, create_link.link_type)
-                    {
-                        emit_signal(Signal::LinkDeleted { action, link_type })?;
+            let link_type = match get_local_record(&delete_link.link_add_address)
+                .as_ref()
+                .map(|record| record.action())
+            {
+                Some(Action::CreateLink(create_link)) => {
+                    match LinkTypes::from_type(create_link.zome_index, create_link.link_type) {
+                        Ok(Some(link_type)) => Some(link_type),
+                        // a link of another zome
+                        _ => return Ok(()),
                     }
-                    Ok(())
-                }
-                _ => {
-                    return Err(wasm_error!(WasmErrorInner::Guest(
-                        "Create Link should exist".to_string()
-                    )));
                 }
-            }
+                _ => None,
+            };
+            emit_signal(Signal::LinkDeleted { action, link_type })?;
+            Ok(())
         }
         Action::Create(_create) => {
-            if let Ok(Some(app_entry)) = get_entry_for_action(&action.hashed.hash) {
-                emit_signal(Signal::EntryCreated { action, app_entry })?;
+            if let Some(app_entry) = get_entry_for_action(&action.hashed.hash)? {
+                match (signal_mode, &app_entry) {
+                    (SignalMode::HeaderOnly, EntryTypes::EncryptedContent(encrypted_content)) => {
+                        emit_signal(Signal::EntryCreatedSummary {
+                            summary: encrypted_content.into(),
+                            action,
+                        })?
+                    }
+                    _ => emit_signal(Signal::EntryCreated { action, app_entry })?,
+                }
             }
             Ok(())
         }
         Action::Update(update) => {
-            if let Ok(Some(app_entry)) = get_entry_for_action(&action.hashed.hash) {
-                if let Ok(Some(original_app_entry)) =
-                    get_entry_for_action(&update.original_action_address)
+            if let Some(app_entry) = get_entry_for_action(&action.hashed.hash)? {
+                if let (SignalMode::HeaderOnly, EntryTypes::EncryptedContent(encrypted_content)) =
+                    (signal_mode, &app_entry)
                 {
-                    emit_signal(Signal::EntryUpdated {
+                    emit_signal(Signal::EntryUpdatedSummary {
+                        summary: encrypted_content.into(),
                         action,
-                        app_entry,
-                        original_app_entry,
                     })?;
+                    return Ok(());
                 }
+                let original_app_entry = get_entry_for_action(&update.original_action_address)?;
+                emit_signal(Signal::EntryUpdated {
+                    action,
+                    app_entry,
+                    original_app_entry,
+                })?;
             }
             Ok(())
         }
         Action::Delete(delete) => {
-            if let Ok(Some(original_app_entry)) = get_entry_for_action(&delete.deletes_address) {
-                emit_signal(Signal::EntryDeleted {
+            let original_app_entry = get_entry_for_action(&delete.deletes_address)?;
+            match (signal_mode, &original_app_entry) {
+                (SignalMode::HeaderOnly, Some(EntryTypes::EncryptedContent(encrypted_content))) => {
+                    emit_signal(Signal::EntryDeletedSummary {
+                        original_summary: encrypted_content.into(),
+                        action,
+                    })?
+                }
+                _ => emit_signal(Signal::EntryDeleted {
                     action,
                     original_app_entry,
-                })?;
+                })?,
             }
             Ok(())
         }
         _ => Ok(()),
     }
 }
+/// A record of the local source chain, `None` if the action isn't on it.
+fn get_local_record(action_hash: &ActionHash) -> Option<Record> {
+    let filter = ChainQueryFilter::new()
+        .sequence_range(ChainQueryFilterRange::ActionHashTerminated(
+            action_hash.clone(),
+            0,
+        ))
+        .include_entries(true);
+    query(filter)
+        .ok()?
+        .into_iter()
+        .find(|record| record.action_address() == action_hash)
+}
 fn get_entry_for_action(action_hash: &ActionHash) -> ExternResult<Option<EntryTypes>> {
-    let record = match get_details(action_hash.clone(), GetOptions::default())? {
-        Some(Details::Record(record_details)) => record_details.record,
-        _ => {
-            return Ok(None);
-        }
+    let Some(record) = get_local_record(action_hash) else {
+        return Ok(None);
     };
     let entry = match record.entry().as_option() {
         Some(entry) => entry,
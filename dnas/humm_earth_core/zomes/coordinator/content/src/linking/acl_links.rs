@@ -1,6 +1,11 @@
-use content_integrity::{EncryptedContent, LinkTypes};
+use content_integrity::{
+    acl_role_principals, original_action_hash, Acl, AclParent, EncryptedContent,
+    EncryptedContentHeader, LinkTypes, ACL_PARENT_INDEX,
+};
 use hdk::{hash_path::path::Component, prelude::*};
 
+use crate::{bulk_acl::AclRoleEntity, Signal};
+
 // TODO: use the public key acl instead of the entity acl
 
 pub fn create_acl_links(
@@ -9,7 +14,7 @@ pub fn create_acl_links(
 ) -> ExternResult<Vec<ActionHash>> {
     // add a link for each author based on the ACK admin and write fields
     let mut acl_link_action_hashes: Vec<ActionHash> = vec![];
-    let owner = encrypted_content.header.acl.owner;
+    let owner = encrypted_content.header.acl.owner.clone();
     let admins: Vec<String> = encrypted_content.header.acl.admin.clone();
     let writers: Vec<String> = encrypted_content
         .header
@@ -97,5 +102,278 @@ pub fn create_acl_links(
             .push(ah.expect(format!("could not create link for reader: '{}'", id).as_str()));
     });
 
+    create_acl_parent_links(
+        &encrypted_content.header.hive_id,
+        &encrypted_content.header.acl,
+        action_hash,
+    )?;
+
     Ok(acl_link_action_hashes)
 }
+
+/// Brings the ACL links of an `EncryptedContent` in line with a new revision: links for
+/// principals that lost a role are deleted and links for principals that gained one are created.
+/// ACL links always point at the original content hash. Emits `AclChanged` when any role changed.
+pub fn update_acl_links(
+    previous_encrypted_content: &EncryptedContent,
+    updated_encrypted_content: &EncryptedContent,
+    original_hash: ActionHash,
+) -> ExternResult<()> {
+    let previous_roles = acl_link_roles(&previous_encrypted_content.header.acl);
+    let updated_roles = acl_link_roles(&updated_encrypted_content.header.acl);
+
+    for (acl_role, id) in previous_roles.iter() {
+        if updated_roles.contains(&(*acl_role, id.clone())) {
+            continue;
+        }
+        let path = acl_path(&previous_encrypted_content.header, id);
+        delete_links_to(path, acl_role_link_type(acl_role)?, &original_hash)?;
+    }
+
+    for (acl_role, id) in updated_roles.iter() {
+        if previous_roles.contains(&(*acl_role, id.clone())) {
+            continue;
+        }
+        let path = acl_path(&updated_encrypted_content.header, id);
+        create_link(
+            path.path_entry_hash()?,
+            original_hash.clone(),
+            acl_role_link_type(acl_role)?,
+            (),
+        )?;
+    }
+
+    update_acl_parent_links(
+        &updated_encrypted_content.header.hive_id,
+        &previous_encrypted_content.header.acl,
+        &updated_encrypted_content.header.acl,
+        original_hash.clone(),
+    )?;
+
+    if previous_encrypted_content.header.inherits_from
+        != updated_encrypted_content.header.inherits_from
+    {
+        if let Some(parent) = &previous_encrypted_content.header.inherits_from {
+            delete_acl_inheritance_link(parent, original_hash.clone())?;
+        }
+        create_acl_inheritance_link(&updated_encrypted_content.header, original_hash.clone())?;
+    }
+
+    let (added, removed) = acl_changes(
+        &previous_encrypted_content.header.acl,
+        &updated_encrypted_content.header.acl,
+    );
+    if !added.is_empty() || !removed.is_empty() {
+        emit_signal(Signal::AclChanged {
+            original_hash,
+            added,
+            removed,
+        })?;
+    }
+    Ok(())
+}
+
+/// The roles granted and revoked between two ACLs, as `(added, removed)`.
+pub fn acl_changes(
+    previous_acl: &Acl,
+    updated_acl: &Acl,
+) -> (Vec<AclRoleEntity>, Vec<AclRoleEntity>) {
+    let previous_roles = acl_link_roles(previous_acl);
+    let updated_roles = acl_link_roles(updated_acl);
+    let added = updated_roles
+        .iter()
+        .filter(|role| !previous_roles.contains(role))
+        .map(|(acl_role, id)| {
+            acl_role_entity(acl_role, id, updated_acl.expires_at.get(id).copied())
+        })
+        .collect();
+    let removed = previous_roles
+        .iter()
+        .filter(|role| !updated_roles.contains(role))
+        .map(|(acl_role, id)| acl_role_entity(acl_role, id, None))
+        .collect();
+    (added, removed)
+}
+
+/// Content and hive policies are also linked from every principal they grant a role to across
+/// the hive, so that ACL queries find the parents of inheriting content of any content type with
+/// one lookup per principal.
+pub fn create_acl_parent_links(
+    hive_id: &str,
+    acl: &Acl,
+    original_hash: ActionHash,
+) -> ExternResult<()> {
+    for (acl_role, id) in acl_link_roles(acl) {
+        create_link(
+            acl_parent_path(hive_id, &id).path_entry_hash()?,
+            original_hash.clone(),
+            acl_role_link_type(acl_role)?,
+            (),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn update_acl_parent_links(
+    hive_id: &str,
+    previous_acl: &Acl,
+    updated_acl: &Acl,
+    original_hash: ActionHash,
+) -> ExternResult<()> {
+    let previous_roles = acl_link_roles(previous_acl);
+    let updated_roles = acl_link_roles(updated_acl);
+    for (acl_role, id) in previous_roles.iter() {
+        if !updated_roles.contains(&(*acl_role, id.clone())) {
+            let path = acl_parent_path(hive_id, id);
+            delete_links_to(path, acl_role_link_type(acl_role)?, &original_hash)?;
+        }
+    }
+    for (acl_role, id) in updated_roles.iter() {
+        if !previous_roles.contains(&(*acl_role, id.clone())) {
+            create_link(
+                acl_parent_path(hive_id, id).path_entry_hash()?,
+                original_hash.clone(),
+                acl_role_link_type(acl_role)?,
+                (),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn delete_acl_parent_links(
+    hive_id: &str,
+    acl: &Acl,
+    original_hash: ActionHash,
+) -> ExternResult<()> {
+    for (acl_role, id) in acl_link_roles(acl) {
+        let path = acl_parent_path(hive_id, &id);
+        delete_links_to(path, acl_role_link_type(acl_role)?, &original_hash)?;
+    }
+    Ok(())
+}
+
+/// The original hashes of the content and hive policies of a hive that grant one of the
+/// principals the role.
+pub fn get_acl_parent_targets(
+    hive_id: &str,
+    link_type: LinkTypes,
+    principals: &[String],
+) -> ExternResult<Vec<ActionHash>> {
+    let mut hashes: Vec<ActionHash> = vec![];
+    for principal in principals {
+        let links = get_links(
+            acl_parent_path(hive_id, principal).path_entry_hash()?,
+            link_type,
+            None,
+        )?;
+        for hash in links
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+        {
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn acl_parent_path(hive_id: &str, id: &str) -> Path {
+    Path::from(vec![
+        Component::from(ACL_PARENT_INDEX),
+        Component::from(hive_id.to_string()),
+        Component::from(id.to_string()),
+    ])
+}
+
+fn delete_links_to(path: Path, link_type: LinkTypes, target: &ActionHash) -> ExternResult<()> {
+    for link in get_links(path.path_entry_hash()?, link_type, None)? {
+        if link.target == AnyLinkableHash::from(target.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn acl_role_entity(acl_role: &str, id: &str, expires_at: Option<Timestamp>) -> AclRoleEntity {
+    AclRoleEntity {
+        acl_role: acl_role.to_string(),
+        entity_id: id.to_string(),
+        expires_at,
+    }
+}
+
+/// Links the original hash of the parent a header inherits from to the inheriting content, so
+/// that ACL queries can find content through the grants of its parents. The tag is the content
+/// type of the inheriting content.
+pub fn create_acl_inheritance_link(
+    header: &EncryptedContentHeader,
+    original_hash: ActionHash,
+) -> ExternResult<Option<ActionHash>> {
+    let Some(parent) = &header.inherits_from else {
+        return Ok(None);
+    };
+    let link_hash = create_link(
+        acl_parent_original_hash(parent)?,
+        original_hash,
+        LinkTypes::AclInheritance,
+        LinkTag::new(header.content_type.clone()),
+    )?;
+    Ok(Some(link_hash))
+}
+
+fn delete_acl_inheritance_link(parent: &AclParent, original_hash: ActionHash) -> ExternResult<()> {
+    let links = get_links(
+        acl_parent_original_hash(parent)?,
+        LinkTypes::AclInheritance,
+        None,
+    )?;
+    for link in links {
+        if link.target == AnyLinkableHash::from(original_hash.clone()) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn acl_parent_original_hash(parent: &AclParent) -> ExternResult<ActionHash> {
+    match parent {
+        AclParent::HivePolicy { hash } | AclParent::Content { hash } => {
+            original_action_hash(hash.clone())
+        }
+    }
+}
+
+pub fn acl_role_link_type(acl_role: &str) -> ExternResult<LinkTypes> {
+    match acl_role {
+        "Owner" => Ok(LinkTypes::HummContentOwner),
+        "Admin" => Ok(LinkTypes::HummContentAdmin),
+        "Writer" => Ok(LinkTypes::HummContentWriter),
+        "Reader" => Ok(LinkTypes::HummContentReader),
+        _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Invalid acl_role"
+        )))),
+    }
+}
+
+fn acl_path(header: &EncryptedContentHeader, id: &str) -> Path {
+    Path::from(vec![
+        Component::from(header.hive_id.clone()),
+        Component::from(header.content_type.clone()),
+        Component::from(id.to_string()),
+    ])
+}
+
+/// The (role, principal) pairs that `create_acl_links` links for an ACL. ACL links don't carry
+/// the expiry of a grant, queries read it from the ACL of the content.
+fn acl_link_roles(acl: &Acl) -> Vec<(&'static str, String)> {
+    ["Owner", "Admin", "Writer", "Reader"]
+        .into_iter()
+        .flat_map(|acl_role| {
+            acl_role_principals(acl, acl_role)
+                .into_iter()
+                .map(move |id| (acl_role, id))
+        })
+        .collect()
+}
@@ -9,7 +9,7 @@ pub fn create_dynamic_links(
     // let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
 
     let mut ahs = vec![];
-    dynamic_links.iter().for_each(|link| {
+    for link in dynamic_links.iter() {
         // TODO: update this to use the acl to find all writers of this content and create corresponding links
         // do we need this or will we always fetch content by dynamic links in the context of a hive and not an author?
         // let author_path = Path::from(vec![
@@ -46,7 +46,7 @@ pub fn create_dynamic_links(
         // );
 
         let hive_ah_res = create_link(
-            hive_path_entry_hash,
+            hive_path_entry_hash.clone(),
             action_hash.clone(),
             LinkTypes::Dynamic,
             (),
@@ -63,7 +63,28 @@ pub fn create_dynamic_links(
         );
 
         ahs.push(hive_ah);
-    });
+
+        // reverse link, so that the values of a content can be read back, e.g. for exports
+        create_link(
+            action_hash.clone(),
+            hive_path_entry_hash,
+            LinkTypes::DynamicLinkValues,
+            LinkTag::new(link.clone()),
+        )?;
+    }
 
     Ok(ahs)
 }
+
+/// The dynamic link values a content was created with. Content created before values were
+/// linked back has none.
+pub fn get_dynamic_link_values(original_hash: ActionHash) -> ExternResult<Vec<String>> {
+    let links = get_links(original_hash, LinkTypes::DynamicLinkValues, None)?;
+    let mut values: Vec<String> = links
+        .into_iter()
+        .filter_map(|link| String::from_utf8(link.tag.into_inner()).ok())
+        .collect();
+    values.sort();
+    values.dedup();
+    Ok(values)
+}
@@ -6,7 +6,7 @@ pub fn create_hive_link(
     action_hash: ActionHash,
 ) -> ExternResult<ActionHash> {
     let hive_path = Path::from(vec![
-        Component::from(encrypted_content.header.hive_id),
+        Component::from(encrypted_content.header.hive_id.clone()),
         Component::from(encrypted_content.header.content_type.clone()),
     ]);
     let hive_ah = create_link(
@@ -16,5 +16,60 @@ pub fn create_hive_link(
         (),
     )?;
 
+    // index the content type under the hive once so that hive wide operations can find it
+    let content_type = encrypted_content.header.content_type;
+    if !get_hive_content_types(encrypted_content.header.hive_id.clone())?.contains(&content_type) {
+        let hive_root_path = Path::from(vec![Component::from(encrypted_content.header.hive_id)]);
+        create_link(
+            hive_root_path.path_entry_hash()?,
+            hive_path.path_entry_hash()?,
+            LinkTypes::HiveContentType,
+            LinkTag::new(content_type),
+        )?;
+    }
+
     Ok(hive_ah)
 }
+
+pub fn get_hive_content_types(hive_id: String) -> ExternResult<Vec<String>> {
+    let hive_root_path = Path::from(vec![Component::from(hive_id)]);
+    let links = get_links(
+        hive_root_path.path_entry_hash()?,
+        LinkTypes::HiveContentType,
+        None,
+    )?;
+    let mut content_types: Vec<String> = links
+        .into_iter()
+        .filter_map(|link| String::from_utf8(link.tag.into_inner()).ok())
+        .collect();
+    content_types.sort();
+    content_types.dedup();
+    Ok(content_types)
+}
+
+/// The original hashes of all content linked to a hive and content type, oldest first.
+pub fn get_hive_content_hashes(
+    hive_id: String,
+    content_type: String,
+) -> ExternResult<Vec<ActionHash>> {
+    let hive_path = Path::from(vec![
+        Component::from(hive_id),
+        Component::from(content_type),
+    ]);
+    let mut links = get_links(hive_path.path_entry_hash()?, LinkTypes::Hive, None)?;
+    links.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.target.to_string().cmp(&b.target.to_string()))
+    });
+    let mut hashes: Vec<ActionHash> = vec![];
+    for hash in links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+    {
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    Ok(hashes)
+}
@@ -4,15 +4,26 @@ use content_integrity::*;
 use hdk::{hash_path::path::Component, prelude::*};
 use time_indexing::*;
 
-pub fn time_index_encrypted_content(ah: ActionHash, content_type: &str) -> ExternResult<TypedPath> {
+/// Indexes new content under its author. `index_time` replaces the time of the action, e.g. to
+/// keep the original creation time of imported content.
+pub fn time_index_encrypted_content(
+    ah: ActionHash,
+    content_type: &str,
+    index_time: Option<Timestamp>,
+) -> ExternResult<TypedPath> {
     let agent_info = agent_info()?;
-    let time = get(ah.clone(), GetOptions::content())?
-        .unwrap()
-        .action()
-        .timestamp();
+    let time = match index_time {
+        Some(index_time) => index_time,
+        None => get(ah.clone(), GetOptions::content())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Could not find the action to index"
+            ))))?
+            .action()
+            .timestamp(),
+    };
     let path = Path::from(vec![
         Component::from(ENCRYPTED_CONTENT_TIME_INDEX),
-        Component::from(agent_info.agent_latest_pubkey.to_string()), // TODO: how to handle agent public key changes?
+        Component::from(agent_info.agent_initial_pubkey.to_string()),
     ]);
     let index = index_item(
         path.typed(LinkTypes::TimePath)?,
@@ -49,6 +60,185 @@ pub fn time_index_encrypted_content(ah: ActionHash, content_type: &str) -> Exter
 //     Ok(index.0)
 // }
 
+/// Indexes new content under its hive and content type, so that the latest content of a hive
+/// can be queried by time range. `index_time` works as in `time_index_encrypted_content`.
+pub fn time_index_hive_content(
+    ah: ActionHash,
+    hive_id: &str,
+    content_type: &str,
+    index_time: Option<Timestamp>,
+) -> ExternResult<TypedPath> {
+    let time = match index_time {
+        Some(index_time) => index_time,
+        None => get(ah.clone(), GetOptions::content())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Could not find the action to index"
+            ))))?
+            .action()
+            .timestamp(),
+    };
+    let index = index_item(
+        hive_content_time_index_path(hive_id, content_type).typed(LinkTypes::TimePath)?,
+        ah.into(),
+        content_type,
+        LinkTypes::TimeItem.try_into().unwrap(),
+        time,
+        &vec![],
+    )?;
+
+    Ok(index.0)
+}
+
+pub fn get_hive_content_time_index_links(
+    hive_id: &str,
+    content_type: &str,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+    limit: Option<usize>,
+) -> ExternResult<(SweepInterval, Vec<(Timestamp, Link)>)> {
+    let begin = start_time.unwrap_or(Timestamp::HOLOCHAIN_EPOCH);
+    let end = end_time.unwrap_or(sys_time()?);
+    let sweep_interval = SweepInterval::new(begin, end)?;
+    get_latest_time_indexed_links(
+        hive_content_time_index_path(hive_id, content_type).typed(LinkTypes::TimePath)?,
+        sweep_interval,
+        limit.unwrap_or(usize::MAX),
+        None,
+        LinkTypes::TimeItem,
+    )
+}
+
+fn hive_content_time_index_path(hive_id: &str, content_type: &str) -> Path {
+    Path::from(vec![
+        Component::from(HIVE_CONTENT_TIME_INDEX),
+        Component::from(hive_id.to_string()),
+        Component::from(content_type.to_string()),
+    ])
+}
+
+/// Indexes a create, update or delete of content in a hive at the time of its action, so that
+/// clients can sync the changes since their last visit. The item name is the operation and the
+/// tag data is the original content hash, see `HiveChangeTag`.
+pub fn time_index_hive_change(
+    hive_id: &str,
+    ah: ActionHash,
+    original_hash: ActionHash,
+    operation: &str,
+) -> ExternResult<TypedPath> {
+    let time = get(ah.clone(), GetOptions::content())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the action to index"
+        ))))?
+        .action()
+        .timestamp();
+    let path = Path::from(vec![
+        Component::from(HIVE_CHANGES_TIME_INDEX),
+        Component::from(hive_id.to_string()),
+    ]);
+    let index = index_item(
+        path.typed(LinkTypes::TimePath)?,
+        ah.into(),
+        operation,
+        LinkTypes::TimeItem.try_into().unwrap(),
+        time,
+        original_hash.get_raw_39(),
+    )?;
+
+    Ok(index.0)
+}
+
+/// The part of the `TimedItemTag` of a hive change that `get_hive_changes_since` reads.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct HiveChangeTag {
+    pub item_type: String,
+    pub custom_data: Vec<u8>,
+}
+
+pub fn get_hive_change_time_index_links(
+    hive_id: &str,
+    since: Timestamp,
+) -> ExternResult<Vec<(Timestamp, Link)>> {
+    let sweep_interval = SweepInterval::new(since, sys_time()?)?;
+    let path = Path::from(vec![
+        Component::from(HIVE_CHANGES_TIME_INDEX),
+        Component::from(hive_id.to_string()),
+    ]);
+    let response = get_latest_time_indexed_links(
+        path.typed(LinkTypes::TimePath)?,
+        sweep_interval,
+        usize::MAX,
+        None,
+        LinkTypes::TimeItem,
+    )?;
+    Ok(response.1)
+}
+
+/// Indexes a create or update of content at the time of its action, under the hive and under
+/// the initial key of the agent who made it. The links target the original content, so that
+/// readers can keep the latest modification of each item and fetch its latest revision.
+pub fn time_index_modification(
+    revision_hash: ActionHash,
+    original_hash: ActionHash,
+    hive_id: &str,
+    content_type: &str,
+) -> ExternResult<()> {
+    let agent_info = agent_info()?;
+    let time = get(revision_hash, GetOptions::content())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the action to index"
+        ))))?
+        .action()
+        .timestamp();
+    let mut paths = vec![modified_time_index_path(
+        "author",
+        &agent_info.agent_initial_pubkey.to_string(),
+    )];
+    if hive_id != "" {
+        paths.push(modified_time_index_path("hive", hive_id));
+    }
+    for path in paths {
+        index_item(
+            path.typed(LinkTypes::TimePath)?,
+            original_hash.clone().into(),
+            content_type,
+            LinkTypes::TimeItem.try_into().unwrap(),
+            time,
+            &vec![],
+        )?;
+    }
+    Ok(())
+}
+
+/// `scope` is either "hive" with a hive ID, or "author" with the initial key of an agent.
+pub fn get_modified_time_index_links(
+    scope: &str,
+    id: &str,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+) -> ExternResult<Vec<(Timestamp, Link)>> {
+    let begin = start_time.unwrap_or(Timestamp::HOLOCHAIN_EPOCH);
+    let end = end_time.unwrap_or(sys_time()?);
+    let sweep_interval = SweepInterval::new(begin, end)?;
+    let response = get_latest_time_indexed_links(
+        modified_time_index_path(scope, id).typed(LinkTypes::TimePath)?,
+        sweep_interval,
+        usize::MAX,
+        None,
+        LinkTypes::TimeItem,
+    )?;
+    Ok(response.1)
+}
+
+fn modified_time_index_path(scope: &str, id: &str) -> Path {
+    Path::from(vec![
+        Component::from(MODIFIED_TIME_INDEX),
+        Component::from(scope.to_string()),
+        Component::from(id.to_string()),
+    ])
+}
+
+/// Time indexed links are keyed on the initial public key of the author, which stays the same
+/// when the agent rotates its key. `author` must therefore be the initial key.
 pub fn get_encrypted_content_time_index_links(
     author: AgentPubKey,
     content_type: &str,
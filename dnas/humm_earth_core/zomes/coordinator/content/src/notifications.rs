@@ -0,0 +1,96 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{entity_profile::get_entity_agents, hive_subscription::get_hive_subscribers, Signal};
+
+/// Tells the agents behind the ACL of a content revision, and the subscribers of its hive and
+/// content type that are members of the cited hive revision, that it was created or updated.
+/// Subscribers are resolved best effort, the agents of the ACL are told even if that fails. Only
+/// the header travels, recipients fetch the content if they need it.
+pub fn notify_content_change(
+    content_hash: ActionHash,
+    original_hash: ActionHash,
+    header: &EncryptedContentHeader,
+) -> ExternResult<()> {
+    let my_agent_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut agents = get_acl_agents(header)?;
+    if let Err(err) = push_hive_subscribers(&mut agents, header) {
+        warn!(
+            "Could not resolve the subscribers of {}: {:?}",
+            header.hive_id, err
+        );
+    }
+    agents.retain(|agent| agent != &my_agent_pub_key);
+    if agents.is_empty() {
+        return Ok(());
+    }
+    send_remote_signal(
+        Signal::ContentNotification {
+            content_hash,
+            original_hash,
+            author: my_agent_pub_key,
+            header: header.clone(),
+        },
+        agents,
+    )
+}
+
+/// The agents that hold an unexpired grant in the ACLs of a header. Agent key principals are read
+/// from the header alone, entity IDs are resolved to the agents of their profile in the entity
+/// directory. Entities that can't be resolved, for example offline, are skipped. Groups and
+/// inherited grants are not resolved.
+pub fn get_acl_agents(header: &EncryptedContentHeader) -> ExternResult<Vec<AgentPubKey>> {
+    let now = sys_time()?;
+    let mut agents: Vec<AgentPubKey> = vec![];
+    for acl in [&header.acl, &header.public_key_acl] {
+        for principal in public_key_readers(acl) {
+            if acl.is_expired(&principal, now) {
+                continue;
+            }
+            if let Ok(agent) = AgentPubKeyB64::from_b64_str(&principal) {
+                push_unique(&mut agents, AgentPubKey::from(agent));
+                continue;
+            }
+            if group_principal_hash(&principal).is_some() {
+                continue;
+            }
+            match get_entity_agents(principal.clone()) {
+                Ok(entity_agents) => {
+                    for agent in entity_agents {
+                        push_unique(&mut agents, agent);
+                    }
+                }
+                Err(err) => warn!("Could not resolve the agents of {}: {:?}", principal, err),
+            }
+        }
+    }
+    Ok(agents)
+}
+
+/// Adds the subscribers of the hive and content type of a header that are members of its cited
+/// hive revision. Subscribers that can't read the content only hear of it as members of the hive.
+fn push_hive_subscribers(
+    agents: &mut Vec<AgentPubKey>,
+    header: &EncryptedContentHeader,
+) -> ExternResult<()> {
+    let Some(hive_revision) = &header.hive_membership else {
+        return Ok(());
+    };
+    let subscribers = get_hive_subscribers(header.hive_id.clone(), header.content_type.clone())?;
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+    let hive = must_get_hive(hive_revision.clone())?;
+    for subscriber in subscribers {
+        if hive.is_member(&subscriber) {
+            push_unique(agents, subscriber);
+        }
+    }
+    Ok(())
+}
+
+fn push_unique<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
@@ -0,0 +1,172 @@
+use content_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    encrypted_content::{
+        commit_encrypted_content_update, get_encrypted_content, get_latest_encrypted_content,
+        get_original_encrypted_content_hash, EncryptedContentResponse,
+    },
+    linking::acl_links::update_acl_links,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OwnershipTransferResponse {
+    pub ownership_transfer: OwnershipTransfer,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransferOwnershipInput {
+    pub content_hash: ActionHash,
+    pub new_owner: String,
+    pub new_owner_agent: AgentPubKey,
+}
+
+/// Proposes handing ownership of an `EncryptedContent` to `new_owner`. Nothing changes until the
+/// `new_owner_agent` calls `accept_ownership_transfer`.
+#[hdk_extern]
+pub fn transfer_ownership(
+    input: TransferOwnershipInput,
+) -> ExternResult<OwnershipTransferResponse> {
+    let original_hash = get_original_encrypted_content_hash(input.content_hash)?;
+    let (encrypted_content, latest_hash) = get_latest_encrypted_content(original_hash.clone())?;
+    let ownership_transfer = OwnershipTransfer {
+        original_content_hash: original_hash.clone(),
+        content_hash: latest_hash,
+        current_owner: encrypted_content.header.acl.owner,
+        new_owner: input.new_owner,
+        new_owner_agent: input.new_owner_agent.clone(),
+    };
+    let action_hash = create_entry(&EntryTypes::OwnershipTransfer(ownership_transfer.clone()))?;
+    create_link(
+        original_hash,
+        action_hash.clone(),
+        LinkTypes::OwnershipTransfers,
+        (),
+    )?;
+    create_link(
+        input.new_owner_agent,
+        action_hash.clone(),
+        LinkTypes::AgentToOwnershipTransfers,
+        (),
+    )?;
+
+    Ok(OwnershipTransferResponse {
+        ownership_transfer,
+        hash: action_hash.to_string(),
+    })
+}
+
+/// Accepts an ownership transfer addressed to this agent: commits the acceptance, the revision
+/// with the new owner and the moved `HummContentOwner` link in a single zome call.
+#[hdk_extern]
+pub fn accept_ownership_transfer(
+    ownership_transfer_hash: ActionHash,
+) -> ExternResult<EncryptedContentResponse> {
+    let ownership_transfer = get_ownership_transfer(ownership_transfer_hash.clone())?;
+    if is_cancelled(ownership_transfer_hash.clone())? {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The ownership transfer was cancelled"
+        ))));
+    }
+    if ownership_transfer.new_owner_agent != agent_info()?.agent_latest_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The ownership transfer is addressed to a different agent"
+        ))));
+    }
+    let (previous_encrypted_content, previous_hash) =
+        get_latest_encrypted_content(ownership_transfer.original_content_hash.clone())?;
+    if previous_hash != ownership_transfer.content_hash {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The content has changed since the transfer was proposed"
+        ))));
+    }
+    let mut updated_encrypted_content = previous_encrypted_content.clone();
+    updated_encrypted_content.header.acl.owner = ownership_transfer.new_owner;
+
+    create_entry(&EntryTypes::OwnershipTransferAcceptance(
+        OwnershipTransferAcceptance {
+            ownership_transfer_hash,
+        },
+    ))?;
+    let (updated_encrypted_content_hash, original_hash) =
+        commit_encrypted_content_update(previous_hash, &mut updated_encrypted_content)?;
+    update_acl_links(
+        &previous_encrypted_content,
+        &updated_encrypted_content,
+        original_hash,
+    )?;
+
+    get_encrypted_content(updated_encrypted_content_hash)
+}
+
+/// Cancels an ownership transfer. If the content is still at the revision the transfer was
+/// proposed against, an unchanged revision is committed first so the transfer can no longer be
+/// accepted.
+#[hdk_extern]
+pub fn cancel_ownership_transfer(ownership_transfer_hash: ActionHash) -> ExternResult<ActionHash> {
+    let ownership_transfer = get_ownership_transfer(ownership_transfer_hash.clone())?;
+    let (mut encrypted_content, latest_hash) =
+        get_latest_encrypted_content(ownership_transfer.original_content_hash)?;
+    if latest_hash == ownership_transfer.content_hash {
+        commit_encrypted_content_update(latest_hash, &mut encrypted_content)?;
+    }
+    delete_entry(ownership_transfer_hash)
+}
+
+/// Ownership transfers proposed to this agent that have not been cancelled.
+#[hdk_extern]
+pub fn list_my_ownership_transfers(_: ()) -> ExternResult<Vec<OwnershipTransferResponse>> {
+    let links = get_links(
+        agent_info()?.agent_latest_pubkey,
+        LinkTypes::AgentToOwnershipTransfers,
+        None,
+    )?;
+    let mut ownership_transfers = vec![];
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(Details::Record(details)) =
+            get_details(action_hash.clone(), GetOptions::default())?
+        else {
+            continue;
+        };
+        if !details.deletes.is_empty() {
+            continue;
+        }
+        let Some(ownership_transfer) = details
+            .record
+            .entry()
+            .to_app_option::<OwnershipTransfer>()
+            .map_err(|e| wasm_error!(e))?
+        else {
+            continue;
+        };
+        ownership_transfers.push(OwnershipTransferResponse {
+            ownership_transfer,
+            hash: action_hash.to_string(),
+        });
+    }
+    Ok(ownership_transfers)
+}
+
+fn is_cancelled(ownership_transfer_hash: ActionHash) -> ExternResult<bool> {
+    match get_details(ownership_transfer_hash, GetOptions::default())? {
+        Some(Details::Record(details)) => Ok(!details.deletes.is_empty()),
+        _ => Ok(false),
+    }
+}
+
+fn get_ownership_transfer(ownership_transfer_hash: ActionHash) -> ExternResult<OwnershipTransfer> {
+    let record = get(ownership_transfer_hash, GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("Could not find the OwnershipTransfer"))
+    ))?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the OwnershipTransfer"
+        ))))
+}
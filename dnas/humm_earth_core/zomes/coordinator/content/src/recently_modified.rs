@@ -0,0 +1,72 @@
+use hdk::prelude::*;
+
+use crate::{
+    encrypted_content::{get_encrypted_content, EncryptedContentResponse},
+    time_indexed_links::get_modified_time_index_links,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListRecentlyModifiedInput {
+    pub hive_id: Option<String>,
+    pub author: Option<AgentPubKey>, // the initial public key of the agent
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecentlyModifiedResponse {
+    pub encrypted_content: EncryptedContentResponse,
+    pub modified_at: Timestamp,
+}
+
+/// The latest revision of each content of a hive or an author modified within a time range, most
+/// recently modified first. Exactly one of `hive_id` and `author` must be given.
+#[hdk_extern]
+pub fn list_recently_modified(
+    input: ListRecentlyModifiedInput,
+) -> ExternResult<Vec<RecentlyModifiedResponse>> {
+    let links = match (input.hive_id, input.author) {
+        (Some(hive_id), None) => {
+            get_modified_time_index_links("hive", &hive_id, input.start_time, input.end_time)?
+        }
+        (None, Some(author)) => get_modified_time_index_links(
+            "author",
+            &author.to_string(),
+            input.start_time,
+            input.end_time,
+        )?,
+        _ => {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "Either a hive_id or an author is required, but not both"
+            ))))
+        }
+    };
+
+    let mut items: Vec<(Timestamp, ActionHash)> = vec![];
+    for (timestamp, link) in links {
+        let Some(original_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        match items.iter_mut().find(|(_, hash)| hash == &original_hash) {
+            Some(item) => item.0 = item.0.max(timestamp),
+            None => items.push((timestamp, original_hash)),
+        }
+    }
+    items.sort_by(|(a_time, a), (b_time, b)| {
+        b_time
+            .cmp(a_time)
+            .then_with(|| a.to_string().cmp(&b.to_string()))
+    });
+    items.truncate(input.limit.unwrap_or(usize::MAX));
+
+    items
+        .into_iter()
+        .map(|(modified_at, original_hash)| {
+            Ok(RecentlyModifiedResponse {
+                encrypted_content: get_encrypted_content(original_hash)?,
+                modified_at,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+use hdk::prelude::*;
+
+/// `Full` signals carry whole entries. `HeaderOnly` signals replace the `bytes` of an
+/// `EncryptedContent` with their length, clients fetch the content when they need it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum SignalMode {
+    #[default]
+    Full,
+    HeaderOnly,
+}
+
+/// The zome properties read by the coordinator, e.g. `signal_mode: HeaderOnly`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SignalProperties {
+    #[serde(default)]
+    signal_mode: SignalMode,
+}
+
+/// How commits are signalled to the UI, set with the `signal_mode` zome property.
+#[hdk_extern]
+pub fn get_signal_mode(_: ()) -> ExternResult<SignalMode> {
+    let properties = zome_info()?.properties;
+    let properties: Option<SignalProperties> = ExternIO(properties.bytes().clone())
+        .decode()
+        .map_err(|e| wasm_error!(e))?;
+    Ok(properties.unwrap_or_default().signal_mode)
+}
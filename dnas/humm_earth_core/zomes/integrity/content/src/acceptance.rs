@@ -0,0 +1,36 @@
+use hdi::prelude::*;
+
+use crate::UnitEntryTypes;
+
+/// The acceptance committed right before an update that needs another agent's consent. `None` if
+/// the previous action isn't an acceptance of `entry_type` or the acceptance was already used.
+pub fn must_get_preceding_acceptance<T>(
+    action: &Update,
+    entry_type: UnitEntryTypes,
+) -> ExternResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let entry_type: EntryType = entry_type.try_into()?;
+    let prev_record = must_get_valid_record(action.prev_action.clone())?;
+    let Action::Create(create) = prev_record.action() else {
+        return Ok(None);
+    };
+    if create.entry_type != entry_type {
+        return Ok(None);
+    }
+    let activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(create.prev_action.clone()),
+    )?;
+    if activity
+        .iter()
+        .any(|item| item.action.hashed.content.entry_hash() == Some(&create.entry_hash))
+    {
+        return Ok(None);
+    }
+    prev_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))
+}
@@ -0,0 +1,33 @@
+use hdi::prelude::*;
+
+/// An `AgentToInitialKey` link is created by an agent that rotated its key, from its latest key
+/// to the initial key its content is indexed under.
+pub fn validate_create_link_agent_to_initial_key(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address.into_agent_pub_key() != Some(action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only link their own key to their initial key",
+        )));
+    }
+    if target_address.into_agent_pub_key().is_none() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An AgentToInitialKey link must target an agent key",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_agent_to_initial_key(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "AgentToInitialKey links cannot be deleted",
+    )))
+}
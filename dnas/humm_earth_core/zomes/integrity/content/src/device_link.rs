@@ -0,0 +1,206 @@
+use hdi::prelude::*;
+
+use crate::{
+    must_get_entity_profile, must_get_preceding_acceptance, original_action_hash, EntityProfile,
+    UnitEntryTypes,
+};
+
+/// A claim by a device of an entity that another device belongs to the same entity. The new
+/// device joins the `EntityProfile` by accepting the claim.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DeviceLinkClaim {
+    pub entity_profile_hash: ActionHash,
+    pub entity_profile_revision: ActionHash,
+    pub new_device: AgentPubKey,
+}
+
+/// The new device's consent to join the entity, see `must_get_preceding_acceptance`.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DeviceLinkAcceptance {
+    pub device_link_claim_hash: ActionHash,
+}
+
+pub fn validate_create_device_link_claim(
+    action: EntryCreationAction,
+    device_link_claim: DeviceLinkClaim,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action_hash(device_link_claim.entity_profile_revision.clone())?
+        != device_link_claim.entity_profile_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited EntityProfile revision does not belong to the EntityProfile",
+        )));
+    }
+    let entity_profile = must_get_entity_profile(device_link_claim.entity_profile_revision)?;
+    if !entity_profile.agents.contains(action.author()) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only a device of the entity can claim another device",
+        )));
+    }
+    if entity_profile
+        .agents
+        .contains(&device_link_claim.new_device)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The device is already linked to the entity",
+        )));
+    }
+    if entity_profile
+        .revoked_agents
+        .iter()
+        .any(|revoked| revoked.agent == device_link_claim.new_device)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A revoked device cannot be linked to the entity again",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_device_link_claim(
+    _action: Update,
+    _device_link_claim: DeviceLinkClaim,
+    _original_action: EntryCreationAction,
+    _original_device_link_claim: DeviceLinkClaim,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Device link claims cannot be updated",
+    )))
+}
+pub fn validate_delete_device_link_claim(
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_device_link_claim: DeviceLinkClaim,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author()
+        && action.author != original_device_link_claim.new_device
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the claiming and the claimed device can close a device link claim",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_device_link_acceptance(
+    action: EntryCreationAction,
+    device_link_acceptance: DeviceLinkAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    let device_link_claim =
+        must_get_device_link_claim(device_link_acceptance.device_link_claim_hash)?;
+    if &device_link_claim.new_device != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the claimed device can accept a device link claim",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_device_link_acceptance(
+    _action: Update,
+    _device_link_acceptance: DeviceLinkAcceptance,
+    _original_action: EntryCreationAction,
+    _original_device_link_acceptance: DeviceLinkAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Device link acceptances cannot be updated",
+    )))
+}
+pub fn validate_delete_device_link_acceptance(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_device_link_acceptance: DeviceLinkAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Device link acceptances cannot be deleted",
+    )))
+}
+
+/// Checks an `EntityProfile` update by an agent that isn't one of its agents yet: the update must
+/// only add that agent and directly follow its acceptance of a device link claim for the profile.
+pub fn validate_device_link(
+    action: &Update,
+    entity_profile: &EntityProfile,
+    original_entity_profile: &EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(acceptance) = must_get_preceding_acceptance::<DeviceLinkAcceptance>(
+        action,
+        UnitEntryTypes::DeviceLinkAcceptance,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents of an entity can update its EntityProfile",
+        )));
+    };
+    let device_link_claim = must_get_device_link_claim(acceptance.device_link_claim_hash)?;
+    if device_link_claim.new_device != action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the claimed device can link itself",
+        )));
+    }
+    if original_action_hash(action.original_action_address.clone())?
+        != device_link_claim.entity_profile_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The accepted device link claim is for a different EntityProfile",
+        )));
+    }
+    if original_entity_profile
+        .revoked_agents
+        .iter()
+        .any(|revoked| revoked.agent == action.author)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A revoked device cannot be linked to the entity again",
+        )));
+    }
+    let mut linked_entity_profile = original_entity_profile.clone();
+    linked_entity_profile.agents.push(action.author.clone());
+    if entity_profile != &linked_entity_profile {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A linked device can only add itself to the EntityProfile",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn must_get_device_link_claim(action_hash: ActionHash) -> ExternResult<DeviceLinkClaim> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the DeviceLinkClaim"
+        ))))
+}
+
+pub fn validate_create_link_device_link_claims(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_device_link_claim(action_hash)?;
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_device_link_claims(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a device link claim link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
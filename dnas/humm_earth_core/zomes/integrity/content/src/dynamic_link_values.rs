@@ -0,0 +1,38 @@
+use hdi::prelude::*;
+
+/// Dynamic link value links go from a content to the `[hive_id, content_type, value]` path it is
+/// listed under, tagged with the value, so that the values of a content can be read back. Only
+/// the author of the content can add them.
+pub fn validate_create_link_dynamic_link_values(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    _target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = base_address
+        .into_action_hash()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "No action hash associated with link"
+        ))))?;
+    let record = must_get_valid_record(action_hash)?;
+    if record.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a content can add its dynamic link values",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_dynamic_link_values(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a dynamic link value can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,5 +1,13 @@
+use std::collections::BTreeMap;
+
 use hdi::prelude::*;
 
+use crate::{
+    content_properties, effective_acl, effective_public_key_acl, validate_acl_parent,
+    validate_author_entity_profile_on_create, validate_author_entity_profile_on_update,
+    validate_hive_membership, validate_ownership_change, UnitEntryTypes,
+};
+
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct EncryptedContent {
@@ -16,11 +24,33 @@ pub struct EncryptedContentHeader {
     pub acl: Acl,
     pub public_key_acl: Acl,
     pub revision_author_signing_public_key: String,
+    /// Where this content inherits further ACL grants from, see `AclParent`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherits_from: Option<AclParent>,
+    /// The `Hive` revision that lists the author, required when `hive_id` refers to a `Hive`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hive_membership: Option<ActionHash>,
+    /// The `EntityProfile` revision through which the author acts for an entity of the ACL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_entity_profile: Option<ActionHash>,
+    /// The `Group` revision through which the entity of `author_entity_profile` writes, when the
+    /// entity is only a writer through a group principal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_group: Option<ActionHash>,
+    /// Counts the content keys `bytes` has been encrypted with. It must increase whenever a
+    /// reader of the public key ACL is removed, so that the removed reader's key envelope no
+    /// longer matches.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub key_epoch: u32,
     // revisionauthor
     // add hash?
     // add signature?
 }
 
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct Acl {
@@ -28,22 +58,216 @@ pub struct Acl {
     pub admin: Vec<String>,
     pub writer: Vec<String>,
     pub reader: Vec<String>,
+    /// When the grants of individual admins, writers or readers stop being valid.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub expires_at: BTreeMap<String, Timestamp>,
+}
+
+/// A pinned revision whose ACL is added to the ACL of an `EncryptedContent`: a `HivePolicy` of
+/// the same hive, or another `EncryptedContent` of the same hive which may itself inherit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AclParent {
+    HivePolicy { hash: ActionHash },
+    Content { hash: ActionHash },
+}
+
+impl Acl {
+    /// Whether the grant of a principal has expired at the given time. The owner never expires.
+    pub fn is_expired(&self, principal: &str, at: Timestamp) -> bool {
+        principal != self.owner
+            && self
+                .expires_at
+                .get(principal)
+                .map_or(false, |expires_at| *expires_at <= at)
+    }
+
+    /// The union of this ACL with an inherited one. The owner stays the same, the owner of the
+    /// parent becomes an admin, and a principal only expires if every grant it holds expires.
+    pub fn inherit(&self, parent: &Acl) -> Acl {
+        let mut acl = self.clone();
+        let parent_admins = std::iter::once(&parent.owner).chain(parent.admin.iter());
+        merge_principals(&mut acl.admin, &self.owner, parent_admins);
+        merge_principals(&mut acl.writer, &self.owner, parent.writer.iter());
+        merge_principals(&mut acl.reader, &self.owner, parent.reader.iter());
+        acl.expires_at = BTreeMap::new();
+        for principal in acl
+            .admin
+            .iter()
+            .chain(acl.writer.iter())
+            .chain(acl.reader.iter())
+        {
+            let expires_at = match (self.grant_expiry(principal), parent.grant_expiry(principal)) {
+                (Some(Some(a)), Some(Some(b))) => Some(a.max(b)),
+                (Some(Some(a)), None) | (None, Some(Some(a))) => Some(a),
+                _ => None,
+            };
+            if let Some(expires_at) = expires_at {
+                acl.expires_at.insert(principal.clone(), expires_at);
+            }
+        }
+        acl
+    }
+
+    /// `None` if the principal holds no grant, otherwise the expiry of its grant.
+    fn grant_expiry(&self, principal: &str) -> Option<Option<Timestamp>> {
+        if principal == self.owner {
+            return Some(None);
+        }
+        let granted = self
+            .admin
+            .iter()
+            .chain(self.writer.iter())
+            .chain(self.reader.iter())
+            .any(|member| member == principal);
+        granted.then(|| self.expires_at.get(principal).copied())
+    }
+}
+
+fn merge_principals<'a>(
+    members: &mut Vec<String>,
+    owner: &str,
+    principals: impl Iterator<Item = &'a String>,
+) {
+    for principal in principals {
+        if principal != owner && !members.contains(principal) {
+            members.push(principal.clone());
+        }
+    }
 }
 
 pub fn validate_create_encrypted_content(
-    _action: EntryCreationAction,
-    _encrypted_content: EncryptedContent,
+    action: EntryCreationAction,
+    encrypted_content: EncryptedContent,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+    match validate_hive_membership(
+        action.author(),
+        action.prev_action(),
+        &encrypted_content.header,
+        None,
+    )? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    match validate_author_entity_profile_on_create(&action, &encrypted_content)? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    validate_acl_parent(&encrypted_content.header)
 }
 pub fn validate_update_encrypted_content(
-    _action: Update,
-    _encrypted_content: EncryptedContent,
+    action: Update,
+    encrypted_content: EncryptedContent,
     _original_action: EntryCreationAction,
-    _original_encrypted_content: EncryptedContent,
+    original_encrypted_content: EncryptedContent,
 ) -> ExternResult<ValidateCallbackResult> {
+    match validate_hive_membership(
+        &action.author,
+        &action.prev_action,
+        &encrypted_content.header,
+        Some(&original_encrypted_content.header),
+    )? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    match validate_writer_not_expired(&action, &encrypted_content, &original_encrypted_content)? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    match validate_acl_parent(&encrypted_content.header)? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    match validate_key_epoch(&encrypted_content, &original_encrypted_content)? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    match validate_author_entity_profile_on_update(
+        &action,
+        &encrypted_content,
+        &original_encrypted_content,
+    )? {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    if encrypted_content.header.acl.owner != original_encrypted_content.header.acl.owner {
+        return validate_ownership_change(&action, &encrypted_content, &original_encrypted_content);
+    }
     Ok(ValidateCallbackResult::Valid)
 }
+/// A time limited grant of the author's own key in the effective public key ACL is checked
+/// against the key directly. Once a grant of the effective entity ACL being updated is time
+/// limited, the update has to cite the entity profile of the author so that
+/// `validate_author_entity_profile_on_update` can check the grant at the time of the action. That
+/// only binds the entity to the author when the entity authority issued the profile, without one
+/// anyone could claim the entity of the owner, so such content cannot be updated at all.
+fn validate_writer_not_expired(
+    action: &Update,
+    encrypted_content: &EncryptedContent,
+    original_encrypted_content: &EncryptedContent,
+) -> ExternResult<ValidateCallbackResult> {
+    if effective_public_key_acl(&original_encrypted_content.header)?
+        .is_expired(&action.author.to_string(), action.timestamp)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The grant of the author's key has expired",
+        )));
+    }
+    if effective_acl(&original_encrypted_content.header)?
+        .expires_at
+        .is_empty()
+    {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    if encrypted_content.header.author_entity_profile.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Updates of content with time limited grants must cite the entity profile of the author",
+        )));
+    }
+    if content_properties()?.entity_authority.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Time limited grants of the entity ACL can only be enforced with an entity authority",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// The key epoch never goes back, and moves forward when a reader of the effective public key ACL
+/// is removed.
+fn validate_key_epoch(
+    encrypted_content: &EncryptedContent,
+    original_encrypted_content: &EncryptedContent,
+) -> ExternResult<ValidateCallbackResult> {
+    let key_epoch = encrypted_content.header.key_epoch;
+    let original_key_epoch = original_encrypted_content.header.key_epoch;
+    if key_epoch < original_key_epoch {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The key epoch of a content cannot decrease",
+        )));
+    }
+    let readers = public_key_readers(&effective_public_key_acl(&encrypted_content.header)?);
+    let original_readers = public_key_readers(&effective_public_key_acl(
+        &original_encrypted_content.header,
+    )?);
+    let reader_removed = original_readers
+        .iter()
+        .any(|reader| !readers.contains(reader));
+    if reader_removed && key_epoch == original_key_epoch {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Removing a reader requires a new key epoch",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Everyone who can decrypt the content: the owner and every reader, writer and admin.
+pub fn public_key_readers(public_key_acl: &Acl) -> Vec<String> {
+    let mut readers = acl_role_principals(public_key_acl, "Reader");
+    if !readers.contains(&public_key_acl.owner) {
+        readers.push(public_key_acl.owner.clone());
+    }
+    readers
+}
+
 pub fn validate_delete_encrypted_content(
     _action: Delete,
     _original_action: EntryCreationAction,
@@ -132,3 +356,163 @@ pub fn validate_delete_link_all_encrypted_content(
         "AllEncryptedContent links cannot be deleted",
     )))
 }
+pub fn validate_create_link_hive_content_type(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    _target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    match String::from_utf8(tag.into_inner()) {
+        Ok(content_type) if !content_type.is_empty() => Ok(ValidateCallbackResult::Valid),
+        _ => Ok(ValidateCallbackResult::Invalid(String::from(
+            "A HiveContentType link must be tagged with its content type",
+        ))),
+    }
+}
+pub fn validate_delete_link_hive_content_type(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "HiveContentType links cannot be deleted",
+    )))
+}
+
+pub fn must_get_encrypted_content(action_hash: ActionHash) -> ExternResult<EncryptedContent> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the EncryptedContent"
+        ))))
+}
+
+/// Walks the update chain of an `EncryptedContent` revision back to the action that created it.
+pub fn original_encrypted_content_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    original_action_hash(action_hash)
+}
+
+/// Walks the update chain of any entry revision back to the action that created it.
+pub fn original_action_hash(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut current = action_hash;
+    loop {
+        match must_get_action(current.clone())?.action() {
+            Action::Update(update) => current = update.original_action_address.clone(),
+            _ => return Ok(current),
+        }
+    }
+}
+
+/// The updates of revisions of `original_hash` with the given entry type that the author committed
+/// up to `chain_top`.
+pub fn must_get_authored_updates(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    entry_type: UnitEntryTypes,
+    original_hash: &ActionHash,
+) -> ExternResult<Vec<(ActionHash, Update)>> {
+    let entry_type: EntryType = entry_type.try_into()?;
+    let activity = must_get_agent_activity(author.clone(), ChainFilter::new(chain_top.clone()))?;
+    let mut updates = vec![];
+    for item in activity {
+        let hash = item.action.hashed.hash.clone();
+        let Action::Update(update) = item.action.hashed.content else {
+            continue;
+        };
+        if update.entry_type == entry_type && &original_action_hash(hash.clone())? == original_hash
+        {
+            updates.push((hash, update));
+        }
+    }
+    Ok(updates)
+}
+
+/// Checks a link from an original entry to one of its revisions, as used to find the latest
+/// revision: only the author of the revision can link it.
+pub fn validate_create_link_revision_updates(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(original_hash), Some(revision_hash)) = (
+        base_address.into_action_hash(),
+        target_address.into_action_hash(),
+    ) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A revision link must link two actions",
+        )));
+    };
+    if revision_hash == original_hash
+        || original_action_hash(revision_hash.clone())? != original_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The linked revision does not belong to the original entry",
+        )));
+    }
+    if must_get_action(revision_hash)?.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a revision can link it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_revision_updates(
+    _action: DeleteLink,
+    _original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Revision links cannot be deleted",
+    )))
+}
+
+/// The agent that set the owner of this revision: the creator of the content, or the agent that
+/// committed the latest ownership transfer in the update chain.
+pub fn owner_agent_for_revision(action_hash: ActionHash) -> ExternResult<AgentPubKey> {
+    let mut current = action_hash;
+    loop {
+        let action = must_get_action(current.clone())?;
+        let Action::Update(update) = action.action() else {
+            return Ok(action.action().author().clone());
+        };
+        let encrypted_content = must_get_encrypted_content(current.clone())?;
+        let original_encrypted_content =
+            must_get_encrypted_content(update.original_action_address.clone())?;
+        if encrypted_content.header.acl.owner != original_encrypted_content.header.acl.owner {
+            return Ok(update.author.clone());
+        }
+        current = update.original_action_address.clone();
+    }
+}
+
+/// The principals that hold an ACL role, with the same hierarchy as the ACL links: admins are
+/// also writers and writers are also readers.
+pub fn acl_role_principals(acl: &Acl, acl_role: &str) -> Vec<String> {
+    let principals: Vec<&String> = match acl_role {
+        "Owner" => vec![&acl.owner],
+        "Admin" => acl.admin.iter().collect(),
+        "Writer" => acl.admin.iter().chain(acl.writer.iter()).collect(),
+        "Reader" => acl
+            .admin
+            .iter()
+            .chain(acl.writer.iter())
+            .chain(acl.reader.iter())
+            .collect(),
+        _ => vec![],
+    };
+    let mut unique_principals: Vec<String> = vec![];
+    for principal in principals {
+        if !unique_principals.contains(principal) {
+            unique_principals.push(principal.clone());
+        }
+    }
+    unique_principals
+}
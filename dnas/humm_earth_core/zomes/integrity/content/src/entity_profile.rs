@@ -0,0 +1,518 @@
+use hdi::{
+    hash_path::path::{Component, Path},
+    prelude::*,
+};
+
+use crate::{
+    acl_role_principals, content_properties, effective_acl, is_acl_writer,
+    must_get_authored_updates, original_action_hash, validate_device_link, Acl, EncryptedContent,
+    UnitEntryTypes, ENTITY_DIRECTORY_INDEX, GROUP_PRINCIPAL_PREFIX,
+};
+
+/// The agents allowed to act for an entity ID used in ACLs, and the public keys content for the
+/// entity is encrypted to. Only agents listed in the current revision can update it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EntityProfile {
+    pub entity_id: String,
+    pub agents: Vec<AgentPubKey>,
+    pub encryption_public_keys: Vec<String>,
+    #[serde(default)]
+    pub revoked_agents: Vec<RevokedAgent>,
+    /// The signature of the entity authority over the `EntityClaim` of the creating agent,
+    /// required when the DNA has an entity authority.
+    #[serde(default)]
+    pub authority_signature: Option<Signature>,
+}
+
+/// What the entity authority signs to let an agent claim an entity ID.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntityClaim {
+    pub entity_id: String,
+    pub agent: AgentPubKey,
+}
+
+/// A device that no longer acts for the entity from `revoked_at` on, see
+/// `validate_author_entity_profile_on_update`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RevokedAgent {
+    pub agent: AgentPubKey,
+    pub revoked_at: Timestamp,
+}
+
+impl EntityProfile {
+    /// Whether the agent was revoked at or before the given time.
+    pub fn is_revoked(&self, agent: &AgentPubKey, at: Timestamp) -> bool {
+        self.revoked_agents
+            .iter()
+            .any(|revoked| &revoked.agent == agent && revoked.revoked_at <= at)
+    }
+}
+
+pub fn validate_create_entity_profile(
+    action: EntryCreationAction,
+    entity_profile: EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    if entity_profile.entity_id.is_empty()
+        || entity_profile.entity_id.starts_with(GROUP_PRINCIPAL_PREFIX)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An EntityProfile needs an entity ID that isn't a group principal",
+        )));
+    }
+    if entity_profile.agents != vec![action.author().clone()] {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An EntityProfile is created by its only agent, other devices join with a DeviceLinkClaim",
+        )));
+    }
+    if entity_profile
+        .revoked_agents
+        .iter()
+        .any(|revoked| entity_profile.agents.contains(&revoked.agent))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A revoked agent cannot act for the entity",
+        )));
+    }
+    validate_entity_claim(action.author(), &entity_profile)
+}
+pub fn validate_update_entity_profile(
+    action: Update,
+    entity_profile: EntityProfile,
+    _original_action: EntryCreationAction,
+    original_entity_profile: EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    if !original_entity_profile.agents.contains(&action.author) {
+        return validate_device_link(&action, &entity_profile, &original_entity_profile);
+    }
+    if entity_profile.entity_id != original_entity_profile.entity_id {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The entity ID of an EntityProfile cannot change",
+        )));
+    }
+    if entity_profile
+        .agents
+        .iter()
+        .any(|agent| !original_entity_profile.agents.contains(agent))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Devices can only join an EntityProfile by accepting a DeviceLinkClaim",
+        )));
+    }
+    validate_revocations(&action, &entity_profile, &original_entity_profile)
+}
+pub fn validate_delete_entity_profile(
+    action: Delete,
+    _original_action: EntryCreationAction,
+    original_entity_profile: EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    if !original_entity_profile.agents.contains(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agents of an entity can delete its EntityProfile",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Revocations are append only: earlier ones cannot change, new ones must name a current agent
+/// that is removed from the agents, and cannot take effect after the update itself.
+fn validate_revocations(
+    action: &Update,
+    entity_profile: &EntityProfile,
+    original_entity_profile: &EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    let previous_count = original_entity_profile.revoked_agents.len();
+    if !entity_profile
+        .revoked_agents
+        .starts_with(&original_entity_profile.revoked_agents)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Device revocations cannot be changed or removed",
+        )));
+    }
+    for revoked in entity_profile.revoked_agents[previous_count..].iter() {
+        if !original_entity_profile.agents.contains(&revoked.agent) {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Only an agent of the entity can be revoked",
+            )));
+        }
+        if revoked.revoked_at > action.timestamp {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "A device revocation cannot take effect in the future",
+            )));
+        }
+    }
+    if entity_profile
+        .revoked_agents
+        .iter()
+        .any(|revoked| entity_profile.agents.contains(&revoked.agent))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A revoked agent cannot act for the entity",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// With an entity authority, only the agent it signed a claim for can create a profile for an
+/// entity ID, which makes the ID exclusive.
+fn validate_entity_claim(
+    author: &AgentPubKey,
+    entity_profile: &EntityProfile,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(entity_authority) = content_properties()?.entity_authority else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let Some(authority_signature) = entity_profile.authority_signature.clone() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An EntityProfile needs the signature of the entity authority",
+        )));
+    };
+    let entity_claim = EntityClaim {
+        entity_id: entity_profile.entity_id.clone(),
+        agent: author.clone(),
+    };
+    if !verify_signature(entity_authority, authority_signature, entity_claim)? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The entity authority did not sign this claim",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Content without an `author_entity_profile` is only valid if the author never created or joined
+/// the profile of an entity that can write it, so that a revoked device cannot skip the checks by
+/// citing nothing. With an entity authority no entity may be able to write it at all, directly or
+/// through a group; without one entity IDs aren't exclusive anyway.
+fn validate_entity_profile_not_required(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    acl: &Acl,
+) -> ExternResult<ValidateCallbackResult> {
+    let writers: Vec<String> = std::iter::once(acl.owner.clone())
+        .chain(acl_role_principals(acl, "Writer"))
+        .filter(|principal| !principal.is_empty())
+        .collect();
+    if content_properties()?.entity_authority.is_some() && !writers.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Content written for an entity must cite the entity profile of its author",
+        )));
+    }
+    if must_get_authored_entity_ids(author, chain_top)?
+        .iter()
+        .any(|entity_id| writers.contains(entity_id))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The author acts for an entity that can write this content and must cite its profile",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The entity IDs of the `EntityProfile` revisions the author committed up to `chain_top`: the
+/// entities the author created or joined as a device.
+fn must_get_authored_entity_ids(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+) -> ExternResult<Vec<String>> {
+    let entry_type: EntryType = UnitEntryTypes::EntityProfile.try_into()?;
+    let mut entity_ids: Vec<String> = vec![];
+    for item in must_get_agent_activity(author.clone(), ChainFilter::new(chain_top.clone()))? {
+        if item.action.hashed.content.entry_type() != Some(&entry_type) {
+            continue;
+        }
+        let entity_id = must_get_entity_profile(item.action.hashed.hash)?.entity_id;
+        if !entity_ids.contains(&entity_id) {
+            entity_ids.push(entity_id);
+        }
+    }
+    Ok(entity_ids)
+}
+
+pub fn must_get_entity_profile(action_hash: ActionHash) -> ExternResult<EntityProfile> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the EntityProfile"
+        ))))
+}
+
+/// Whether the agent acts for the entity according to a pinned `EntityProfile` revision. Entity
+/// IDs aren't exclusive on the DHT, so this only proves what the cited profile says; ACLs that
+/// need stronger guarantees should rely on the public key ACL.
+pub fn agent_acts_for_entity(
+    agent: &AgentPubKey,
+    entity_id: &str,
+    entity_profile_revision: ActionHash,
+) -> ExternResult<bool> {
+    let entity_profile = must_get_entity_profile(entity_profile_revision)?;
+    Ok(entity_profile.entity_id == entity_id && entity_profile.agents.contains(agent))
+}
+
+/// Checks the `author_entity_profile` a new `EncryptedContent` cites: it must list the author, not
+/// revoke it, be the same or a later revision than any revision of the profile the author
+/// committed, and its entity must be the owner, an admin or a writer of the content, possibly
+/// through the cited `author_group`. Revocations that only other devices committed later cannot be
+/// found deterministically, which is why the coordinator always cites the latest revision.
+pub fn validate_author_entity_profile_on_create(
+    action: &EntryCreationAction,
+    encrypted_content: &EncryptedContent,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = action.author();
+    let Some(entity_profile_revision) = encrypted_content.header.author_entity_profile.clone()
+    else {
+        return validate_entity_profile_not_required(
+            author,
+            action.prev_action(),
+            &encrypted_content.header.acl,
+        );
+    };
+    let entity_profile = must_get_entity_profile(entity_profile_revision.clone())?;
+    if !entity_profile.agents.contains(author)
+        || entity_profile.is_revoked(author, *action.timestamp())
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The author does not act for the cited entity",
+        )));
+    }
+    for (authored_revision, _) in must_get_authored_updates(
+        author,
+        action.prev_action(),
+        UnitEntryTypes::EntityProfile,
+        &original_action_hash(entity_profile_revision.clone())?,
+    )? {
+        if !is_same_or_later_revision(entity_profile_revision.clone(), &authored_revision)? {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Content cannot cite an earlier EntityProfile revision than its author committed",
+            )));
+        }
+    }
+    let acl = &encrypted_content.header.acl;
+    if entity_profile.entity_id != acl.owner
+        && !is_acl_writer(
+            acl,
+            &entity_profile.entity_id,
+            encrypted_content.header.author_group.clone(),
+            *action.timestamp(),
+        )?
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited entity cannot write this content",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Once a revision cites an entity profile, every update must cite the same or a later revision of
+/// it, not be signed by a revoked device, and act for the owner or an unexpired writer. A group
+/// revision cited before can only be replaced by the same or a later revision of that group.
+pub fn validate_author_entity_profile_on_update(
+    action: &Update,
+    encrypted_content: &EncryptedContent,
+    original_encrypted_content: &EncryptedContent,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(entity_profile_revision) = encrypted_content.header.author_entity_profile.clone()
+    else {
+        return match original_encrypted_content.header.author_entity_profile {
+            Some(_) => Ok(ValidateCallbackResult::Invalid(String::from(
+                "Updates of content that cites an entity profile must cite one too",
+            ))),
+            None => validate_entity_profile_not_required(
+                &action.author,
+                &action.prev_action,
+                &effective_acl(&original_encrypted_content.header)?,
+            ),
+        };
+    };
+    if let Some(previous_revision) = original_encrypted_content
+        .header
+        .author_entity_profile
+        .clone()
+    {
+        if must_get_entity_profile(previous_revision.clone())?
+            .is_revoked(&action.author, action.timestamp)
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "The author is a revoked device of the cited entity",
+            )));
+        }
+        if original_action_hash(previous_revision.clone())?
+            == original_action_hash(entity_profile_revision.clone())?
+            && !is_same_or_later_revision(entity_profile_revision.clone(), &previous_revision)?
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "An update cannot cite an earlier EntityProfile revision than the content it updates",
+            )));
+        }
+    }
+    let entity_profile = must_get_entity_profile(entity_profile_revision)?;
+    if !entity_profile.agents.contains(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The author does not act for the cited entity",
+        )));
+    }
+    let entity_id = &entity_profile.entity_id;
+    let acl = effective_acl(&original_encrypted_content.header)?;
+    let is_owner = entity_id == &acl.owner;
+    if let (Some(author_group), Some(previous_author_group)) = (
+        encrypted_content.header.author_group.clone(),
+        original_encrypted_content.header.author_group.clone(),
+    ) {
+        if original_action_hash(author_group.clone())?
+            == original_action_hash(previous_author_group.clone())?
+            && !is_same_or_later_revision(author_group, &previous_author_group)?
+        {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "An update cannot cite an earlier Group revision than the content it updates",
+            )));
+        }
+    }
+    let is_writer = is_acl_writer(
+        &acl,
+        entity_id,
+        encrypted_content.header.author_group.clone(),
+        action.timestamp,
+    )?;
+    let is_new_owner = entity_id == &encrypted_content.header.acl.owner
+        && encrypted_content.header.acl.owner != original_encrypted_content.header.acl.owner;
+    if !is_owner && !is_writer && !is_new_owner {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited entity cannot write this content",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether `revision` is `earlier_revision` or follows it through a chain of updates.
+fn is_same_or_later_revision(
+    revision: ActionHash,
+    earlier_revision: &ActionHash,
+) -> ExternResult<bool> {
+    let mut current = revision;
+    loop {
+        if &current == earlier_revision {
+            return Ok(true);
+        }
+        match must_get_action(current)?.action() {
+            Action::Update(update) => current = update.original_action_address.clone(),
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// An `AgentToEntityProfiles` link is created by the agent itself, so an agent is never linked to
+/// a profile it didn't create or join.
+pub fn validate_create_link_agent_to_entity_profiles(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_entity_profile(action_hash)?;
+    if base_address.into_agent_pub_key() != Some(action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only link themselves to an EntityProfile",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// Besides the agent itself, an agent of the profile can unlink an agent it removed from it.
+pub fn validate_delete_link_agent_to_entity_profiles(
+    action: DeleteLink,
+    original_action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_action.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let (Some(agent), Some(entity_profile_hash)) = (
+        base_address.into_agent_pub_key(),
+        target_address.into_action_hash(),
+    ) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An AgentToEntityProfiles link goes from an agent to an EntityProfile",
+        )));
+    };
+    for (update_hash, update) in must_get_authored_updates(
+        &action.author,
+        &action.prev_action,
+        UnitEntryTypes::EntityProfile,
+        &entity_profile_hash,
+    )? {
+        let previous_entity_profile = must_get_entity_profile(update.original_action_address)?;
+        let entity_profile = must_get_entity_profile(update_hash)?;
+        if previous_entity_profile.agents.contains(&action.author)
+            && previous_entity_profile.agents.contains(&agent)
+            && !entity_profile.agents.contains(&agent)
+        {
+            return Ok(ValidateCallbackResult::Valid);
+        }
+    }
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Only the agent and the agents of the EntityProfile that removed it can unlink it",
+    )))
+}
+
+/// An `EntityDirectory` link goes from the path of the entity ID to a profile of that entity,
+/// created by one of its agents.
+pub fn validate_create_link_entity_directory(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let entity_profile = must_get_entity_profile(action_hash)?;
+    if base_address.into_entry_hash()
+        != Some(entity_directory_path_hash(&entity_profile.entity_id)?)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An EntityProfile must be linked from the path of its entity ID",
+        )));
+    }
+    if !entity_profile.agents.contains(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only an agent of the entity can link its EntityProfile",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_entity_directory(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an entity directory link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The hash of the path that links an entity ID to its profiles.
+pub fn entity_directory_path_hash(entity_id: &str) -> ExternResult<EntryHash> {
+    Path::from(vec![
+        Component::from(ENTITY_DIRECTORY_INDEX),
+        Component::from(entity_id.to_string()),
+    ])
+    .path_entry_hash()
+}
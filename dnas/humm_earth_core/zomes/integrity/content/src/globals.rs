@@ -1 +1,19 @@
 pub const ENCRYPTED_CONTENT_TIME_INDEX: &'static str = "encrypted_content_time";
+pub const GROUP_PRINCIPAL_PREFIX: &str = "group:";
+pub const GROUP_MEMBERSHIP_INDEX: &str = "group_membership";
+pub const MAX_ACL_INHERITANCE_DEPTH: usize = 8;
+pub const HIVE_MEMBERSHIP_INDEX: &str = "hive_membership";
+pub const HIVE_DIRECTORY_ANCHOR: &str = "hive_directory";
+pub const HIVE_SEARCH_INDEX: &str = "hive_search";
+pub const HIVE_SEARCH_PREFIX_LENGTH: usize = 3;
+pub const MAX_HIVE_SEARCH_TOKEN_LENGTH: usize = 64;
+pub const ENTITY_DIRECTORY_INDEX: &str = "entity_directory";
+pub const HIVE_CHANGES_TIME_INDEX: &str = "hive_changes_time";
+pub const HIVE_CONTENT_TIME_INDEX: &str = "hive_content_time";
+pub const ACL_PARENT_INDEX: &str = "acl_parent";
+pub const MODIFIED_TIME_INDEX: &str = "modified_time";
+/// How far a client supplied index time may lie before the action, wide enough for imports of
+/// historical content.
+pub const MAX_INDEX_TIME_BACKDATE_SECONDS: i64 = 20 * 365 * 24 * 60 * 60;
+/// How far a client supplied index time may lie after the action, to allow for clock skew.
+pub const MAX_INDEX_TIME_FUTURE_SECONDS: i64 = 5 * 60;
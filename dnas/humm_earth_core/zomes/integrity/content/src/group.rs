@@ -0,0 +1,160 @@
+use hdi::prelude::*;
+
+use crate::{acl_role_principals, original_action_hash, Acl, GROUP_PRINCIPAL_PREFIX};
+
+/// A named set of entity IDs that can be granted an ACL role as a whole through the principal
+/// `group:<original group action hash>`.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Group {
+    pub name: String,
+    pub owner: String,
+    pub members: Vec<String>,
+}
+
+impl Group {
+    /// Whether the entity belongs to the group: its owner and its members do.
+    pub fn includes(&self, entity_id: &str) -> bool {
+        self.owner == entity_id || self.members.iter().any(|member| member == entity_id)
+    }
+}
+
+pub fn validate_create_group(
+    _action: EntryCreationAction,
+    group: Group,
+) -> ExternResult<ValidateCallbackResult> {
+    if group.owner.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A Group must have an owner",
+        )));
+    }
+    if group
+        .members
+        .iter()
+        .any(|member| member.starts_with(GROUP_PRINCIPAL_PREFIX))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Groups cannot be members of other groups",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_group(
+    action: Update,
+    _group: Group,
+    original_action: EntryCreationAction,
+    _original_group: Group,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a Group can update it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_group(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_group: Group,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a Group can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Group membership links are only maintained by the creator of the group, the only agent that
+/// can update it.
+pub fn validate_create_link_group_members(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let record = must_get_valid_record(action_hash)?;
+    let _group: crate::Group = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Linked action must reference an entry"
+        ))))?;
+    if !matches!(record.action(), Action::Create(_)) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A GroupMember link must point to the original Group",
+        )));
+    }
+    if record.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a Group can link its members",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_group_members(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a Group can unlink its members",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The ACL principal that grants a role to every member of a group.
+pub fn group_principal(original_group_hash: &ActionHash) -> String {
+    format!("{}{}", GROUP_PRINCIPAL_PREFIX, original_group_hash)
+}
+
+/// The original group hash of a `group:<hash>` principal, or `None` for plain entity IDs.
+pub fn group_principal_hash(principal: &str) -> Option<ActionHash> {
+    let hash = principal.strip_prefix(GROUP_PRINCIPAL_PREFIX)?;
+    ActionHashB64::from_b64_str(hash).ok().map(ActionHash::from)
+}
+
+pub fn must_get_group(action_hash: ActionHash) -> ExternResult<Group> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the Group"
+        ))))
+}
+
+/// Whether the entity holds an unexpired writer grant of the ACL at the given time, directly or
+/// through a group principal. Group principals are resolved with the cited group revision, the
+/// same way `check_acl_permission` resolves them with the latest one.
+pub fn is_acl_writer(
+    acl: &Acl,
+    entity_id: &str,
+    group_revision: Option<ActionHash>,
+    at: Timestamp,
+) -> ExternResult<bool> {
+    let writers = acl_role_principals(acl, "Writer");
+    if writers.iter().any(|writer| writer == entity_id) && !acl.is_expired(entity_id, at) {
+        return Ok(true);
+    }
+    let Some(group_revision) = group_revision else {
+        return Ok(false);
+    };
+    let principal = group_principal(&original_action_hash(group_revision.clone())?);
+    if !writers.contains(&principal) || acl.is_expired(&principal, at) {
+        return Ok(false);
+    }
+    Ok(must_get_group(group_revision)?.includes(entity_id))
+}
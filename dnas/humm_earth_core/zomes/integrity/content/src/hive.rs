@@ -0,0 +1,409 @@
+use hdi::{
+    hash_path::path::{Component, Path},
+    prelude::*,
+};
+
+use crate::{
+    must_get_authored_updates, original_action_hash, validate_invited_join, EncryptedContentHeader,
+    UnitEntryTypes, HIVE_MEMBERSHIP_INDEX, MAX_HIVE_SEARCH_TOKEN_LENGTH,
+};
+
+/// A hive whose `hive_id` is the original action hash of this entry. Content of such a hive can
+/// only be written by its owner, admins and members. Hive IDs that aren't action hashes are
+/// legacy hives without membership.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Hive {
+    pub name: String,
+    pub owner: AgentPubKey,
+    pub admins: Vec<AgentPubKey>,
+    pub members: Vec<AgentPubKey>,
+    pub membership_policy: MembershipPolicy,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub visibility: HiveVisibility,
+    #[serde(default)]
+    pub removals: Vec<HiveRemoval>,
+    #[serde(default)]
+    pub revoked_invitations: Vec<ActionHash>,
+}
+
+/// The record of an agent leaving or being removed from a hive. Removals are never dropped from
+/// later revisions, see `validate_hive_membership`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HiveRemoval {
+    pub agent: AgentPubKey,
+    pub removed_at: Timestamp,
+}
+
+/// The tag of a `HiveMember` link: the hive revision that lists the member.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct HiveMemberTag {
+    pub hive_revision: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MembershipPolicy {
+    Open,
+    InviteOnly,
+    RequestToJoin,
+}
+
+/// Public hives are listed in the hive directory and can be found with `search_hives`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum HiveVisibility {
+    Public,
+    #[default]
+    Private,
+}
+
+impl Hive {
+    pub fn is_admin(&self, agent: &AgentPubKey) -> bool {
+        &self.owner == agent || self.admins.contains(agent)
+    }
+
+    pub fn is_member(&self, agent: &AgentPubKey) -> bool {
+        self.is_admin(agent) || self.members.contains(agent)
+    }
+
+    /// The owner, admins and members of the hive.
+    pub fn agents(&self) -> Vec<AgentPubKey> {
+        let mut agents: Vec<AgentPubKey> = vec![];
+        for agent in std::iter::once(&self.owner)
+            .chain(self.admins.iter())
+            .chain(self.members.iter())
+        {
+            if !agents.contains(agent) {
+                agents.push(agent.clone());
+            }
+        }
+        agents
+    }
+
+    /// When the agent last left or was removed from the hive.
+    pub fn last_removal(&self, agent: &AgentPubKey) -> Option<Timestamp> {
+        self.removals
+            .iter()
+            .filter(|removal| &removal.agent == agent)
+            .map(|removal| removal.removed_at)
+            .max()
+    }
+}
+
+pub fn validate_create_hive(
+    action: EntryCreationAction,
+    hive: Hive,
+) -> ExternResult<ValidateCallbackResult> {
+    if &hive.owner != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The creator of a Hive must be its owner",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_hive(
+    action: Update,
+    hive: Hive,
+    _original_action: EntryCreationAction,
+    original_hive: Hive,
+) -> ExternResult<ValidateCallbackResult> {
+    if hive.owner != original_hive.owner && action.author != original_hive.owner {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner can hand over a Hive",
+        )));
+    }
+    match validate_hive_removals(&action, &hive, &original_hive) {
+        ValidateCallbackResult::Valid => (),
+        result => return Ok(result),
+    }
+    if !hive
+        .revoked_invitations
+        .starts_with(&original_hive.revoked_invitations)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Revoked invitations cannot be dropped from a Hive",
+        )));
+    }
+    if original_hive.is_admin(&action.author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    match self_membership_change(&action.author, &hive, &original_hive) {
+        Some(MembershipChange::Leave) => Ok(ValidateCallbackResult::Valid),
+        Some(MembershipChange::Join) => match original_hive.membership_policy {
+            MembershipPolicy::Open => Ok(ValidateCallbackResult::Valid),
+            _ => validate_invited_join(&action, &original_hive),
+        },
+        None => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner and admins can update a Hive",
+        ))),
+    }
+}
+pub fn validate_delete_hive(
+    action: Delete,
+    _original_action: EntryCreationAction,
+    original_hive: Hive,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_hive.owner {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner can delete a Hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Every agent that leaves the hive gets a removal record no later than the update, and earlier
+/// records are kept.
+fn validate_hive_removals(
+    action: &Update,
+    hive: &Hive,
+    original_hive: &Hive,
+) -> ValidateCallbackResult {
+    if !hive.removals.starts_with(&original_hive.removals) {
+        return ValidateCallbackResult::Invalid(String::from(
+            "Removals cannot be dropped from a Hive",
+        ));
+    }
+    let added_removals = &hive.removals[original_hive.removals.len()..];
+    if added_removals
+        .iter()
+        .any(|removal| removal.removed_at > action.timestamp)
+    {
+        return ValidateCallbackResult::Invalid(String::from(
+            "A removal cannot be recorded after the update",
+        ));
+    }
+    let agents = hive.agents();
+    for agent in original_hive.agents() {
+        let removed = !agents.contains(&agent);
+        let recorded = added_removals.iter().any(|removal| removal.agent == agent);
+        if removed != recorded {
+            return ValidateCallbackResult::Invalid(String::from(
+                "The removals of a Hive update must match the agents it removes",
+            ));
+        }
+    }
+    ValidateCallbackResult::Valid
+}
+
+pub enum MembershipChange {
+    Join,
+    Leave,
+}
+
+/// Whether the update only adds or removes the agent itself from the members of the hive.
+pub fn self_membership_change(
+    agent: &AgentPubKey,
+    hive: &Hive,
+    original_hive: &Hive,
+) -> Option<MembershipChange> {
+    let mut joined = original_hive.clone();
+    joined.members.push(agent.clone());
+    if !original_hive.is_member(agent) && hive == &joined {
+        return Some(MembershipChange::Join);
+    }
+    let mut left = original_hive.clone();
+    left.members.retain(|member| member != agent);
+    left.removals = hive.removals.clone();
+    if original_hive.members.contains(agent) && hive == &left {
+        return Some(MembershipChange::Leave);
+    }
+    None
+}
+
+/// A `HiveMember` link is tagged with a hive revision that lists the member. It is created by an
+/// admin of that revision or of the revision it updates, or by the member itself, whose presence
+/// in the revision shows that it joined validly.
+pub fn validate_create_link_hive_members(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let Ok(hive_member_tag) =
+        HiveMemberTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A HiveMember link must be tagged with the Hive revision that lists the member",
+        )));
+    };
+    let hive_revision = hive_member_tag.hive_revision;
+    if original_action_hash(hive_revision.clone())? != action_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited Hive revision does not belong to the linked Hive",
+        )));
+    }
+    let hive = must_get_hive(hive_revision.clone())?;
+    let Some(member) = hive.agents().into_iter().find(|agent| {
+        hive_membership_path_hash(agent).ok() == base_address.clone().into_entry_hash()
+    }) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited Hive revision does not list the linked member",
+        )));
+    };
+    if member == action.author || hive.is_admin(&action.author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    if let Action::Update(update) = must_get_action(hive_revision)?.action() {
+        if must_get_hive(update.original_action_address.clone())?.is_admin(&action.author) {
+            return Ok(ValidateCallbackResult::Valid);
+        }
+    }
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Only the admins of a Hive and the member itself can link a member",
+    )))
+}
+pub fn validate_delete_link_hive_members(
+    action: DeleteLink,
+    original_action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_action.author
+        || base_address.into_entry_hash() == Some(hive_membership_path_hash(&action.author)?)
+    {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    // an admin of the revision that listed the member, or of the revision it updates
+    let mut create_link = original_action;
+    create_link.author = action.author;
+    validate_create_link_hive_members(create_link, base_address, target_address, tag)
+}
+
+/// The hash of the path that links an agent to the hives it belongs to.
+pub fn hive_membership_path_hash(agent: &AgentPubKey) -> ExternResult<EntryHash> {
+    Path::from(vec![
+        Component::from(HIVE_MEMBERSHIP_INDEX),
+        Component::from(agent.to_string()),
+    ])
+    .path_entry_hash()
+}
+
+pub fn validate_create_link_hive_directory(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_hive(action_hash)?;
+    if tag.0.len() > MAX_HIVE_SEARCH_TOKEN_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Hive directory tags are limited to one search token",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Directory and search links are removed along with a hive revision that unlists the hive, so
+/// they can be deleted by their author or by an agent that updated the hive as an admin earlier
+/// in its chain.
+pub fn validate_delete_link_hive_directory(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_action.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let hive_hash = target_address
+        .into_action_hash()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "No action hash associated with link"
+        ))))?;
+    for (_, update) in must_get_authored_updates(
+        &action.author,
+        &action.prev_action,
+        UnitEntryTypes::Hive,
+        &hive_hash,
+    )? {
+        if must_get_hive(update.original_action_address)?.is_admin(&action.author) {
+            return Ok(ValidateCallbackResult::Valid);
+        }
+    }
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Only the admins of a Hive can unlist it",
+    )))
+}
+
+pub fn must_get_hive(action_hash: ActionHash) -> ExternResult<Hive> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the Hive"
+        ))))
+}
+
+/// The original hash of the `Hive` a hive ID refers to, `None` for legacy string hive IDs.
+pub fn hive_action_hash(hive_id: &str) -> Option<ActionHash> {
+    ActionHashB64::from_b64_str(hive_id)
+        .ok()
+        .map(ActionHash::from)
+}
+
+/// Checks that the author of content in a `Hive` is listed in the cited hive revision, and that the
+/// revision is newer than any removal of the author recorded in the hive revisions the author
+/// committed up to `chain_top` or in the revision cited by the content being updated. Later
+/// revisions that only other admins committed cannot be found deterministically, which is why the
+/// coordinator refuses to cite anything but the latest revision.
+pub fn validate_hive_membership(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    header: &EncryptedContentHeader,
+    previous_header: Option<&EncryptedContentHeader>,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(hive_hash) = hive_action_hash(&header.hive_id) else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let Some(hive_membership) = header.hive_membership.clone() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Content of a Hive must cite the Hive revision that lists its author",
+        )));
+    };
+    if original_action_hash(hive_membership.clone())? != hive_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited Hive revision does not belong to the hive of the content",
+        )));
+    }
+    if !must_get_hive(hive_membership.clone())?.is_member(author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The author is not a member of the Hive",
+        )));
+    }
+    let mut known_revisions: Vec<ActionHash> =
+        must_get_authored_updates(author, chain_top, UnitEntryTypes::Hive, &hive_hash)?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+    known_revisions.extend(
+        previous_header.and_then(|previous_header| previous_header.hive_membership.clone()),
+    );
+    let cited_at = must_get_action(hive_membership)?.action().timestamp();
+    for revision in known_revisions {
+        if let Some(removed_at) = must_get_hive(revision)?.last_removal(author) {
+            if cited_at <= removed_at {
+                return Ok(ValidateCallbackResult::Invalid(String::from(
+                    "The cited Hive revision predates the removal of the author",
+                )));
+            }
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
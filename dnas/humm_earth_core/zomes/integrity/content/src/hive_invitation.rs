@@ -0,0 +1,351 @@
+use hdi::prelude::*;
+
+use crate::{
+    must_get_hive, must_get_preceding_acceptance, original_action_hash, Hive, MembershipPolicy,
+    UnitEntryTypes,
+};
+
+/// An invitation from an admin of a `Hive` to an agent. `hive_revision` is the revision in which
+/// the inviter was an admin.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Invitation {
+    pub hive_hash: ActionHash,
+    pub hive_revision: ActionHash,
+    pub invitee: AgentPubKey,
+}
+
+/// The invitee's consent to join the hive, see `must_get_preceding_acceptance`. `hive_revision`
+/// is the revision that the joining update replaces.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct InvitationAcceptance {
+    pub invitation_hash: ActionHash,
+    pub hive_revision: ActionHash,
+}
+
+/// A request by an agent to be added to a `Hive` with the `RequestToJoin` membership policy. An
+/// admin approves it by adding the requester to the members.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct JoinRequest {
+    pub hive_hash: ActionHash,
+    pub hive_revision: ActionHash,
+    pub requester: AgentPubKey,
+    pub message: String,
+}
+
+pub fn validate_create_invitation(
+    action: EntryCreationAction,
+    invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    let hive = match must_get_hive_revision(&invitation.hive_hash, &invitation.hive_revision)? {
+        Ok(hive) => hive,
+        Err(result) => return Ok(result),
+    };
+    if !hive.is_admin(action.author()) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner and admins of a Hive can invite agents",
+        )));
+    }
+    if hive.is_member(&invitation.invitee) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The invitee is already a member of the Hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_invitation(
+    _action: Update,
+    _invitation: Invitation,
+    _original_action: EntryCreationAction,
+    _original_invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Invitations cannot be updated",
+    )))
+}
+pub fn validate_delete_invitation(
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() && action.author != original_invitation.invitee {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the inviter can revoke and only the invitee can decline an invitation",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_invitation_acceptance(
+    action: EntryCreationAction,
+    invitation_acceptance: InvitationAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    let invitation = must_get_invitation(invitation_acceptance.invitation_hash.clone())?;
+    if &invitation.invitee != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the invitee can accept an invitation",
+        )));
+    }
+    if original_action_hash(invitation_acceptance.hive_revision)? != invitation.hive_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The accepted Hive revision does not belong to the invited Hive",
+        )));
+    }
+    // a declined invitation is deleted by the invitee, earlier in its own chain
+    let activity = must_get_agent_activity(
+        action.author().clone(),
+        ChainFilter::new(action.prev_action().clone()),
+    )?;
+    if activity
+        .iter()
+        .any(|item| match &item.action.hashed.content {
+            Action::Delete(delete) => {
+                delete.deletes_address == invitation_acceptance.invitation_hash
+            }
+            _ => false,
+        })
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The invitation has been declined",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_invitation_acceptance(
+    _action: Update,
+    _invitation_acceptance: InvitationAcceptance,
+    _original_action: EntryCreationAction,
+    _original_invitation_acceptance: InvitationAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Invitation acceptances cannot be updated",
+    )))
+}
+pub fn validate_delete_invitation_acceptance(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_invitation_acceptance: InvitationAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Invitation acceptances cannot be deleted",
+    )))
+}
+
+pub fn validate_create_join_request(
+    action: EntryCreationAction,
+    join_request: JoinRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    if &join_request.requester != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The author of a JoinRequest must be the requester",
+        )));
+    }
+    let hive = match must_get_hive_revision(&join_request.hive_hash, &join_request.hive_revision)? {
+        Ok(hive) => hive,
+        Err(result) => return Ok(result),
+    };
+    if hive.membership_policy != MembershipPolicy::RequestToJoin {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The Hive does not accept join requests",
+        )));
+    }
+    if hive.is_member(&join_request.requester) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The requester is already a member of the Hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_join_request(
+    _action: Update,
+    _join_request: JoinRequest,
+    _original_action: EntryCreationAction,
+    _original_join_request: JoinRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Join requests cannot be updated",
+    )))
+}
+pub fn validate_delete_join_request(
+    action: Delete,
+    _original_action: EntryCreationAction,
+    original_join_request: JoinRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_join_request.requester {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    if !must_get_hive(original_join_request.hive_revision)?.is_admin(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the requester and the admins of the Hive can close a join request",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Checks a `Hive` update in which a non admin adds itself to the members of a hive that isn't
+/// open: it must follow the agent's acceptance of an invitation to this revision of the hive, from
+/// an agent that is still an admin, which hasn't been revoked.
+pub fn validate_invited_join(
+    action: &Update,
+    original_hive: &Hive,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(acceptance) = must_get_preceding_acceptance::<InvitationAcceptance>(
+        action,
+        UnitEntryTypes::InvitationAcceptance,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "This Hive can only be joined by invitation or request",
+        )));
+    };
+    if acceptance.hive_revision != action.original_action_address {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The invitation was accepted for a different Hive revision",
+        )));
+    }
+    if original_hive
+        .revoked_invitations
+        .contains(&acceptance.invitation_hash)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The invitation has been revoked",
+        )));
+    }
+    let invitation_record = must_get_valid_record(acceptance.invitation_hash.clone())?;
+    let invitation = must_get_invitation(acceptance.invitation_hash)?;
+    if invitation.invitee != action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the invitee can join a Hive through an invitation",
+        )));
+    }
+    if original_action_hash(action.original_action_address.clone())? != invitation.hive_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The accepted invitation is for a different Hive",
+        )));
+    }
+    if !original_hive.is_admin(invitation_record.action().author()) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The inviter is no longer an admin of the Hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn must_get_invitation(action_hash: ActionHash) -> ExternResult<Invitation> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the Invitation"
+        ))))
+}
+
+/// The hive revision an invitation or join request cites, or the invalid result if it doesn't
+/// belong to the hive.
+fn must_get_hive_revision(
+    hive_hash: &ActionHash,
+    hive_revision: &ActionHash,
+) -> ExternResult<Result<Hive, ValidateCallbackResult>> {
+    if &original_action_hash(hive_revision.clone())? != hive_hash {
+        return Ok(Err(ValidateCallbackResult::Invalid(String::from(
+            "The cited Hive revision does not belong to the Hive",
+        ))));
+    }
+    Ok(Ok(must_get_hive(hive_revision.clone())?))
+}
+
+pub fn validate_create_link_invitations(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_invitation(action_hash)?;
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_invitations(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an invitation link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A `HiveJoinRequests` link goes from the hive to a join request, created by the requester.
+pub fn validate_create_link_join_requests(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let join_request = must_get_join_request(action_hash)?;
+    if base_address.into_action_hash() != Some(join_request.hive_hash) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A join request must be linked from its Hive",
+        )));
+    }
+    if action.author != join_request.requester {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the requester can link a join request",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_join_requests(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_action.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let join_request = must_get_join_request(action_hash)?;
+    if !must_get_hive(join_request.hive_revision)?.is_admin(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the requester and the admins of the Hive can delete a join request link",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn must_get_join_request(action_hash: ActionHash) -> ExternResult<JoinRequest> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the JoinRequest"
+        ))))
+}
@@ -0,0 +1,266 @@
+use hdi::prelude::*;
+
+use crate::{
+    hive_action_hash, must_get_authored_updates, must_get_encrypted_content, must_get_hive,
+    original_action_hash, Acl, AclParent, EncryptedContentHeader, UnitEntryTypes,
+    MAX_ACL_INHERITANCE_DEPTH,
+};
+
+/// ACL grants shared by every `EncryptedContent` of a hive that inherits from it, so that large
+/// ACLs don't have to be copied into each header. `hive_revision` is the `Hive` revision in which
+/// the author is an admin, for hives that have a `Hive` entry.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct HivePolicy {
+    pub hive_id: String,
+    pub acl: Acl,
+    pub public_key_acl: Acl,
+    #[serde(default)]
+    pub hive_revision: Option<ActionHash>,
+}
+
+pub fn validate_create_hive_policy(
+    action: EntryCreationAction,
+    hive_policy: HivePolicy,
+) -> ExternResult<ValidateCallbackResult> {
+    if hive_policy.hive_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A HivePolicy must belong to a hive",
+        )));
+    }
+    validate_hive_policy_admin(action.author(), &hive_policy)
+}
+pub fn validate_update_hive_policy(
+    action: Update,
+    hive_policy: HivePolicy,
+    original_action: EntryCreationAction,
+    original_hive_policy: HivePolicy,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a HivePolicy can update it",
+        )));
+    }
+    if hive_policy.hive_id != original_hive_policy.hive_id {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A HivePolicy cannot be moved to another hive",
+        )));
+    }
+    validate_hive_policy_admin(&action.author, &hive_policy)
+}
+pub fn validate_delete_hive_policy(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_hive_policy: HivePolicy,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the creator of a HivePolicy can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only the owner and admins of a `Hive` can write its policies. Legacy string hives have no
+/// admins to check against.
+fn validate_hive_policy_admin(
+    author: &AgentPubKey,
+    hive_policy: &HivePolicy,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(hive_hash) = hive_action_hash(&hive_policy.hive_id) else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let Some(hive_revision) = hive_policy.hive_revision.clone() else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A HivePolicy of a Hive must cite the Hive revision that lists its author as an admin",
+        )));
+    };
+    if original_action_hash(hive_revision.clone())? != hive_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The cited Hive revision does not belong to the hive of the policy",
+        )));
+    }
+    if !must_get_hive(hive_revision)?.is_admin(author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the owner and admins of a Hive can write its policies",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn must_get_hive_policy(action_hash: ActionHash) -> ExternResult<HivePolicy> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the HivePolicy"
+        ))))
+}
+
+/// Checks that the parent a header inherits from exists, belongs to the same hive and that the
+/// inheritance chain stays within `MAX_ACL_INHERITANCE_DEPTH`.
+pub fn validate_acl_parent(
+    header: &EncryptedContentHeader,
+) -> ExternResult<ValidateCallbackResult> {
+    match effective_acl(header) {
+        Ok(_) => Ok(ValidateCallbackResult::Valid),
+        Err(WasmError {
+            error: WasmErrorInner::Guest(reason),
+            ..
+        }) => Ok(ValidateCallbackResult::Invalid(reason)),
+        Err(e) => Err(e),
+    }
+}
+
+/// The entity ACL of a header merged with everything it inherits. Validation reads the pinned
+/// parent revisions, so the result never changes once the header is committed.
+pub fn effective_acl(header: &EncryptedContentHeader) -> ExternResult<Acl> {
+    Ok(inherited_acls(header)?
+        .into_iter()
+        .fold(header.acl.clone(), |acl, (parent_acl, _)| {
+            acl.inherit(&parent_acl)
+        }))
+}
+
+/// The public key ACL of a header merged with everything it inherits.
+pub fn effective_public_key_acl(header: &EncryptedContentHeader) -> ExternResult<Acl> {
+    Ok(inherited_acls(header)?
+        .into_iter()
+        .fold(header.public_key_acl.clone(), |acl, (_, parent_acl)| {
+            acl.inherit(&parent_acl)
+        }))
+}
+
+/// The (entity ACL, public key ACL) pairs a header inherits, nearest parent first.
+fn inherited_acls(header: &EncryptedContentHeader) -> ExternResult<Vec<(Acl, Acl)>> {
+    let mut acls: Vec<(Acl, Acl)> = vec![];
+    let mut parent = header.inherits_from.clone();
+    for _ in 0..MAX_ACL_INHERITANCE_DEPTH {
+        let (hive_id, acl, public_key_acl, next_parent) = match parent {
+            None => return Ok(acls),
+            Some(AclParent::HivePolicy { hash }) => {
+                let hive_policy = must_get_hive_policy(hash)?;
+                (
+                    hive_policy.hive_id,
+                    hive_policy.acl,
+                    hive_policy.public_key_acl,
+                    None,
+                )
+            }
+            Some(AclParent::Content { hash }) => {
+                let parent_header = must_get_encrypted_content(hash)?.header;
+                (
+                    parent_header.hive_id,
+                    parent_header.acl,
+                    parent_header.public_key_acl,
+                    parent_header.inherits_from,
+                )
+            }
+        };
+        if hive_id != header.hive_id {
+            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "ACLs can only be inherited from the same hive"
+            ))));
+        }
+        acls.push((acl, public_key_acl));
+        parent = next_parent;
+    }
+    match parent {
+        None => Ok(acls),
+        Some(_) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "ACL inheritance is limited to {} levels",
+            MAX_ACL_INHERITANCE_DEPTH
+        )))),
+    }
+}
+
+pub fn validate_create_link_acl_inheritance(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let encrypted_content = must_get_encrypted_content(action_hash)?;
+    if encrypted_content.header.inherits_from.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The linked EncryptedContent does not inherit an ACL",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// ACL inheritance links are deleted by their author, or by an agent that updated the inheriting
+/// content, as moving it to another parent does.
+pub fn validate_delete_link_acl_inheritance(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author == original_action.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let original_hash = target
+        .into_action_hash()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "No action hash associated with link"
+        ))))?;
+    if must_get_authored_updates(
+        &action.author,
+        &action.prev_action,
+        UnitEntryTypes::EncryptedContent,
+        &original_hash,
+    )?
+    .is_empty()
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an ACL inheritance link or an updater of the content can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Hive policy links are created by the author of the policy.
+pub fn validate_create_link_hive_policies(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_hive_policy(action_hash.clone())?;
+    if must_get_action(action_hash)?.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a HivePolicy can link it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_hive_policies(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a HivePolicy can unlink it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
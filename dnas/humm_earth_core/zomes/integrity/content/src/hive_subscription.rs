@@ -0,0 +1,31 @@
+use hdi::prelude::*;
+
+/// Subscriber links go from the `[hive_id, content_type]` path to the subscribing agent. Agents
+/// can only subscribe themselves.
+pub fn validate_create_link_hive_subscribers(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if target_address != AnyLinkableHash::from(action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only subscribe themselves to a hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_hive_subscribers(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the subscriber can unsubscribe from a hive",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,124 @@
+use hdi::prelude::*;
+
+use crate::{
+    acl_role_principals, effective_public_key_acl, must_get_encrypted_content,
+    original_encrypted_content_hash, public_key_readers,
+};
+
+/// The symmetric key of an `EncryptedContent` revision, wrapped for one agent of its public key
+/// ACL. The key itself never leaves the clients, only `wrapped_key` is published.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct KeyEnvelope {
+    pub content_hash: ActionHash,
+    pub recipient: AgentPubKey,
+    pub wrapped_key: SerializedBytes,
+}
+
+/// The envelope must be for the owner or a reader of the effective public key ACL of the content
+/// revision, and be issued by the author of that revision or one of its writers. Expired grants
+/// can neither issue nor receive envelopes.
+pub fn validate_create_key_envelope(
+    action: EntryCreationAction,
+    key_envelope: KeyEnvelope,
+) -> ExternResult<ValidateCallbackResult> {
+    let content_record = must_get_valid_record(key_envelope.content_hash.clone())?;
+    let encrypted_content = must_get_encrypted_content(key_envelope.content_hash)?;
+    let public_key_acl = effective_public_key_acl(&encrypted_content.header)?;
+    let at = *action.timestamp();
+
+    let recipient = key_envelope.recipient.to_string();
+    let is_recipient = public_key_readers(&public_key_acl).contains(&recipient);
+    if !is_recipient || public_key_acl.is_expired(&recipient, at) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The recipient of a KeyEnvelope must be able to read the content",
+        )));
+    }
+
+    let author = action.author().to_string();
+    let is_issuer = action.author() == content_record.action().author()
+        || author == public_key_acl.owner
+        || acl_role_principals(&public_key_acl, "Writer").contains(&author);
+    if !is_issuer || public_key_acl.is_expired(&author, at) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author or a writer of the content can issue a KeyEnvelope",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_key_envelope(
+    _action: Update,
+    _key_envelope: KeyEnvelope,
+    _original_action: EntryCreationAction,
+    _original_key_envelope: KeyEnvelope,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Key envelopes cannot be updated",
+    )))
+}
+pub fn validate_delete_key_envelope(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_key_envelope: KeyEnvelope,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the issuer of a KeyEnvelope can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn must_get_key_envelope(action_hash: ActionHash) -> ExternResult<KeyEnvelope> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the KeyEnvelope"
+        ))))
+}
+
+/// Key envelope links go from the original content to the envelope, tagged with the recipient.
+pub fn validate_create_link_key_envelopes(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let key_envelope = must_get_key_envelope(action_hash)?;
+    if base_address
+        != AnyLinkableHash::from(original_encrypted_content_hash(key_envelope.content_hash)?)
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A KeyEnvelope must be linked from the content it belongs to",
+        )));
+    }
+    if tag.into_inner() != key_envelope.recipient.to_string().into_bytes() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A KeyEnvelope link must be tagged with its recipient",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_key_envelopes(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a key envelope link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,14 +1,54 @@
+pub mod acceptance;
+pub mod agent_key;
+pub mod device_link;
+pub mod dynamic_link_values;
 pub mod encrypted_content;
+pub mod entity_profile;
 pub mod globals;
+pub mod group;
+pub mod hive;
+pub mod hive_invitation;
+pub mod hive_policy;
+pub mod hive_subscription;
+pub mod key_envelope;
+pub mod ownership_transfer;
+pub mod properties;
+pub mod time_index;
+pub use acceptance::*;
+pub use agent_key::*;
+pub use device_link::*;
+pub use dynamic_link_values::*;
 pub use encrypted_content::*;
+pub use entity_profile::*;
 pub use globals::*;
+pub use group::*;
 use hdi::prelude::*;
-#[derive(Serialize, Deserialize)]
+pub use hive::*;
+pub use hive_invitation::*;
+pub use hive_policy::*;
+pub use hive_subscription::*;
+pub use key_envelope::*;
+pub use ownership_transfer::*;
+pub use properties::*;
+pub use time_index::*;
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 #[hdk_entry_defs]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     EncryptedContent(EncryptedContent),
+    OwnershipTransfer(OwnershipTransfer),
+    OwnershipTransferAcceptance(OwnershipTransferAcceptance),
+    Group(Group),
+    HivePolicy(HivePolicy),
+    Hive(Hive),
+    Invitation(Invitation),
+    InvitationAcceptance(InvitationAcceptance),
+    JoinRequest(JoinRequest),
+    EntityProfile(EntityProfile),
+    DeviceLinkClaim(DeviceLinkClaim),
+    DeviceLinkAcceptance(DeviceLinkAcceptance),
+    KeyEnvelope(KeyEnvelope),
 }
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
@@ -24,6 +64,29 @@ pub enum LinkTypes {
     HummContentAdmin,
     HummContentWriter,
     HummContentReader,
+    OwnershipTransfers,
+    AgentToOwnershipTransfers,
+    HiveContentType,
+    GroupUpdates,
+    GroupMember,
+    HivePolicies,
+    HivePolicyUpdates,
+    AclInheritance,
+    HiveUpdates,
+    HiveMember,
+    HiveInvitations,
+    AgentInvitations,
+    HiveJoinRequests,
+    HiveDirectory,
+    HiveSearch,
+    EntityDirectory,
+    AgentToEntityProfiles,
+    EntityProfileUpdates,
+    AgentToDeviceLinkClaims,
+    KeyEnvelopes,
+    HiveSubscribers,
+    DynamicLinkValues,
+    AgentToInitialKey,
 }
 #[hdk_extern]
 pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
@@ -39,24 +102,12 @@ pub fn validate_agent_joining(
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, action } => match app_entry {
-                EntryTypes::EncryptedContent(encrypted_content) => {
-                    validate_create_encrypted_content(
-                        EntryCreationAction::Create(action),
-                        encrypted_content,
-                    )
-                }
-            },
+            OpEntry::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
             OpEntry::UpdateEntry {
                 app_entry, action, ..
-            } => match app_entry {
-                EntryTypes::EncryptedContent(encrypted_content) => {
-                    validate_create_encrypted_content(
-                        EntryCreationAction::Update(action),
-                        encrypted_content,
-                    )
-                }
-            },
+            } => validate_create_app_entry(EntryCreationAction::Update(action), app_entry),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterUpdate(update_entry) => match update_entry {
@@ -65,20 +116,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 original_app_entry,
                 app_entry,
                 action,
-            } => match (app_entry, original_app_entry) {
-                (
-                    EntryTypes::EncryptedContent(encrypted_content),
-                    EntryTypes::EncryptedContent(original_encrypted_content),
-                ) => validate_update_encrypted_content(
-                    action,
-                    encrypted_content,
-                    original_action,
-                    original_encrypted_content,
-                ),
-                _ => Ok(ValidateCallbackResult::Invalid(
-                    "Original and updated entry types must be the same".to_string(),
-                )),
-            },
+            } => validate_update_app_entry(action, app_entry, original_action, original_app_entry),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterDelete(delete_entry) => match delete_entry {
@@ -86,11 +124,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 original_action,
                 original_app_entry,
                 action,
-            } => match original_app_entry {
-                EntryTypes::EncryptedContent(encrypted_content) => {
-                    validate_delete_encrypted_content(action, original_action, encrypted_content)
-                }
-            },
+            } => validate_delete_app_entry(action, original_action, original_app_entry),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterCreateLink {
@@ -99,34 +133,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             target_address,
             tag,
             action,
-        } => match link_type {
-            LinkTypes::EncryptedContentUpdates => validate_create_link_encrypted_content_updates(
-                action,
-                base_address,
-                target_address,
-                tag,
-            ),
-            // TODO
-            LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentOwner => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::TimeItem => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
-        },
+        } => validate_create_link_of_type(link_type, action, base_address, target_address, tag),
         FlatOp::RegisterDeleteLink {
             link_type,
             base_address,
@@ -134,44 +141,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             tag,
             original_action,
             action,
-        } => match link_type {
-            LinkTypes::EncryptedContentUpdates => validate_delete_link_encrypted_content_updates(
-                action,
-                original_action,
-                base_address,
-                target_address,
-                tag,
-            ),
-            // TODO
-            LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentOwner => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::TimeItem => Ok(ValidateCallbackResult::Valid),
-            // TODO
-            LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
-        },
+        } => validate_delete_link_of_type(
+            link_type,
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
         FlatOp::StoreRecord(store_record) => match store_record {
-            OpRecord::CreateEntry { app_entry, action } => match app_entry {
-                EntryTypes::EncryptedContent(encrypted_content) => {
-                    validate_create_encrypted_content(
-                        EntryCreationAction::Create(action),
-                        encrypted_content,
-                    )
-                }
-            },
+            OpRecord::CreateEntry { app_entry, action } => {
+                validate_create_app_entry(EntryCreationAction::Create(action), app_entry)
+            }
             OpRecord::UpdateEntry {
                 original_action_hash,
                 app_entry,
@@ -190,39 +171,33 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         ));
                     }
                 };
-                match app_entry {
-                    EntryTypes::EncryptedContent(encrypted_content) => {
-                        let result = validate_create_encrypted_content(
-                            EntryCreationAction::Update(action.clone()),
-                            encrypted_content.clone(),
-                        )?;
-                        if let ValidateCallbackResult::Valid = result {
-                            let original_encrypted_content: Option<EncryptedContent> =
-                                original_record
-                                    .entry()
-                                    .to_app_option()
-                                    .map_err(|e| wasm_error!(e))?;
-                            let original_encrypted_content = match original_encrypted_content {
-                                Some(encrypted_content) => encrypted_content,
-                                None => {
-                                    return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
-                                }
-                            };
-                            validate_update_encrypted_content(
-                                action,
-                                encrypted_content,
-                                original_action,
-                                original_encrypted_content,
-                            )
-                        } else {
-                            Ok(result)
+                let result = validate_create_app_entry(
+                    EntryCreationAction::Update(action.clone()),
+                    app_entry.clone(),
+                )?;
+                if let ValidateCallbackResult::Valid = result {
+                    let original_app_entry = match original_record_app_entry(
+                        &original_record,
+                        &original_action,
+                    )? {
+                        Some(original_app_entry) => original_app_entry,
+                        None => {
+                            return Ok(
+                                    ValidateCallbackResult::Invalid(
+                                        "The updated entry type must be the same as the original entry type"
+                                            .to_string(),
+                                    ),
+                                );
                         }
-                    }
+                    };
+                    validate_update_app_entry(
+                        action,
+                        app_entry,
+                        original_action,
+                        original_app_entry,
+                    )
+                } else {
+                    Ok(result)
                 }
             }
             OpRecord::DeleteEntry {
@@ -242,31 +217,22 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         ));
                     }
                 };
-                let app_entry_type = match original_action.entry_type() {
-                    EntryType::App(app_entry_type) => app_entry_type,
-                    _ => {
+                if !matches!(original_action.entry_type(), EntryType::App(_)) {
+                    return Ok(ValidateCallbackResult::Valid);
+                }
+                if original_record.entry().as_option().is_none() {
+                    if original_action.entry_type().visibility().is_public() {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Original record for a delete of a public entry must contain an entry"
+                                .to_string(),
+                        ));
+                    } else {
                         return Ok(ValidateCallbackResult::Valid);
                     }
-                };
-                let entry = match original_record.entry().as_option() {
-                    Some(entry) => entry,
-                    None => {
-                        if original_action.entry_type().visibility().is_public() {
-                            return Ok(
-                                    ValidateCallbackResult::Invalid(
-                                        "Original record for a delete of a public entry must contain an entry"
-                                            .to_string(),
-                                    ),
-                                );
-                        } else {
-                            return Ok(ValidateCallbackResult::Valid);
-                        }
-                    }
-                };
-                let original_app_entry = match EntryTypes::deserialize_from_type(
-                    app_entry_type.zome_index.clone(),
-                    app_entry_type.entry_index.clone(),
-                    &entry,
+                }
+                let original_app_entry = match original_record_app_entry(
+                    &original_record,
+                    &original_action,
                 )? {
                     Some(app_entry) => app_entry,
                     None => {
@@ -278,15 +244,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             );
                     }
                 };
-                match original_app_entry {
-                    EntryTypes::EncryptedContent(original_encrypted_content) => {
-                        validate_delete_encrypted_content(
-                            action,
-                            original_action,
-                            original_encrypted_content,
-                        )
-                    }
-                }
+                validate_delete_app_entry(action, original_action, original_app_entry)
             }
             OpRecord::CreateLink {
                 base_address,
@@ -294,36 +252,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 tag,
                 link_type,
                 action,
-            } => match link_type {
-                LinkTypes::EncryptedContentUpdates => {
-                    validate_create_link_encrypted_content_updates(
-                        action,
-                        base_address,
-                        target_address,
-                        tag,
-                    )
-                }
-                // TODO
-                LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::HummContentOwner => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::TimeItem => Ok(ValidateCallbackResult::Valid),
-                // TODO
-                LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
-            },
+            } => validate_create_link_of_type(link_type, action, base_address, target_address, tag),
             OpRecord::DeleteLink {
                 original_action_hash,
                 base_address,
@@ -347,37 +276,14 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         return Ok(ValidateCallbackResult::Valid);
                     }
                 };
-                match link_type {
-                    LinkTypes::EncryptedContentUpdates => {
-                        validate_delete_link_encrypted_content_updates(
-                            action,
-                            create_link.clone(),
-                            base_address,
-                            create_link.target_address,
-                            create_link.tag,
-                        )
-                    }
-                    // TODO
-                    LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::HummContentOwner => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::TimeItem => Ok(ValidateCallbackResult::Valid),
-                    // TODO
-                    LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
-                }
+                validate_delete_link_of_type(
+                    link_type,
+                    action,
+                    create_link.clone(),
+                    base_address,
+                    create_link.target_address,
+                    create_link.tag,
+                )
             }
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
             OpRecord::UpdatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
@@ -412,3 +318,514 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         },
     }
 }
+fn original_record_app_entry(
+    original_record: &Record,
+    original_action: &EntryCreationAction,
+) -> ExternResult<Option<EntryTypes>> {
+    let (EntryType::App(app_entry_type), Some(entry)) = (
+        original_action.entry_type(),
+        original_record.entry().as_option(),
+    ) else {
+        return Ok(None);
+    };
+    EntryTypes::deserialize_from_type(
+        app_entry_type.zome_index.clone(),
+        app_entry_type.entry_index.clone(),
+        entry,
+    )
+}
+fn validate_create_app_entry(
+    action: EntryCreationAction,
+    app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match app_entry {
+        EntryTypes::EncryptedContent(encrypted_content) => {
+            validate_create_encrypted_content(action, encrypted_content)
+        }
+        EntryTypes::OwnershipTransfer(ownership_transfer) => {
+            validate_create_ownership_transfer(action, ownership_transfer)
+        }
+        EntryTypes::OwnershipTransferAcceptance(ownership_transfer_acceptance) => {
+            validate_create_ownership_transfer_acceptance(action, ownership_transfer_acceptance)
+        }
+        EntryTypes::Group(group) => validate_create_group(action, group),
+        EntryTypes::HivePolicy(hive_policy) => validate_create_hive_policy(action, hive_policy),
+        EntryTypes::Hive(hive) => validate_create_hive(action, hive),
+        EntryTypes::Invitation(invitation) => validate_create_invitation(action, invitation),
+        EntryTypes::InvitationAcceptance(invitation_acceptance) => {
+            validate_create_invitation_acceptance(action, invitation_acceptance)
+        }
+        EntryTypes::JoinRequest(join_request) => validate_create_join_request(action, join_request),
+        EntryTypes::EntityProfile(entity_profile) => {
+            validate_create_entity_profile(action, entity_profile)
+        }
+        EntryTypes::DeviceLinkClaim(device_link_claim) => {
+            validate_create_device_link_claim(action, device_link_claim)
+        }
+        EntryTypes::DeviceLinkAcceptance(device_link_acceptance) => {
+            validate_create_device_link_acceptance(action, device_link_acceptance)
+        }
+        EntryTypes::KeyEnvelope(key_envelope) => validate_create_key_envelope(action, key_envelope),
+    }
+}
+fn validate_update_app_entry(
+    action: Update,
+    app_entry: EntryTypes,
+    original_action: EntryCreationAction,
+    original_app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match (app_entry, original_app_entry) {
+        (
+            EntryTypes::EncryptedContent(encrypted_content),
+            EntryTypes::EncryptedContent(original_encrypted_content),
+        ) => validate_update_encrypted_content(
+            action,
+            encrypted_content,
+            original_action,
+            original_encrypted_content,
+        ),
+        (
+            EntryTypes::OwnershipTransfer(ownership_transfer),
+            EntryTypes::OwnershipTransfer(original_ownership_transfer),
+        ) => validate_update_ownership_transfer(
+            action,
+            ownership_transfer,
+            original_action,
+            original_ownership_transfer,
+        ),
+        (
+            EntryTypes::OwnershipTransferAcceptance(ownership_transfer_acceptance),
+            EntryTypes::OwnershipTransferAcceptance(original_ownership_transfer_acceptance),
+        ) => validate_update_ownership_transfer_acceptance(
+            action,
+            ownership_transfer_acceptance,
+            original_action,
+            original_ownership_transfer_acceptance,
+        ),
+        (EntryTypes::Group(group), EntryTypes::Group(original_group)) => {
+            validate_update_group(action, group, original_action, original_group)
+        }
+        (EntryTypes::HivePolicy(hive_policy), EntryTypes::HivePolicy(original_hive_policy)) => {
+            validate_update_hive_policy(action, hive_policy, original_action, original_hive_policy)
+        }
+        (EntryTypes::Hive(hive), EntryTypes::Hive(original_hive)) => {
+            validate_update_hive(action, hive, original_action, original_hive)
+        }
+        (EntryTypes::Invitation(invitation), EntryTypes::Invitation(original_invitation)) => {
+            validate_update_invitation(action, invitation, original_action, original_invitation)
+        }
+        (
+            EntryTypes::InvitationAcceptance(invitation_acceptance),
+            EntryTypes::InvitationAcceptance(original_invitation_acceptance),
+        ) => validate_update_invitation_acceptance(
+            action,
+            invitation_acceptance,
+            original_action,
+            original_invitation_acceptance,
+        ),
+        (EntryTypes::JoinRequest(join_request), EntryTypes::JoinRequest(original_join_request)) => {
+            validate_update_join_request(
+                action,
+                join_request,
+                original_action,
+                original_join_request,
+            )
+        }
+        (
+            EntryTypes::EntityProfile(entity_profile),
+            EntryTypes::EntityProfile(original_entity_profile),
+        ) => validate_update_entity_profile(
+            action,
+            entity_profile,
+            original_action,
+            original_entity_profile,
+        ),
+        (
+            EntryTypes::DeviceLinkClaim(device_link_claim),
+            EntryTypes::DeviceLinkClaim(original_device_link_claim),
+        ) => validate_update_device_link_claim(
+            action,
+            device_link_claim,
+            original_action,
+            original_device_link_claim,
+        ),
+        (
+            EntryTypes::DeviceLinkAcceptance(device_link_acceptance),
+            EntryTypes::DeviceLinkAcceptance(original_device_link_acceptance),
+        ) => validate_update_device_link_acceptance(
+            action,
+            device_link_acceptance,
+            original_action,
+            original_device_link_acceptance,
+        ),
+        (EntryTypes::KeyEnvelope(key_envelope), EntryTypes::KeyEnvelope(original_key_envelope)) => {
+            validate_update_key_envelope(
+                action,
+                key_envelope,
+                original_action,
+                original_key_envelope,
+            )
+        }
+        _ => Ok(ValidateCallbackResult::Invalid(
+            "Original and updated entry types must be the same".to_string(),
+        )),
+    }
+}
+fn validate_delete_app_entry(
+    action: Delete,
+    original_action: EntryCreationAction,
+    original_app_entry: EntryTypes,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_app_entry {
+        EntryTypes::EncryptedContent(encrypted_content) => {
+            validate_delete_encrypted_content(action, original_action, encrypted_content)
+        }
+        EntryTypes::OwnershipTransfer(ownership_transfer) => {
+            validate_delete_ownership_transfer(action, original_action, ownership_transfer)
+        }
+        EntryTypes::OwnershipTransferAcceptance(ownership_transfer_acceptance) => {
+            validate_delete_ownership_transfer_acceptance(
+                action,
+                original_action,
+                ownership_transfer_acceptance,
+            )
+        }
+        EntryTypes::Group(group) => validate_delete_group(action, original_action, group),
+        EntryTypes::HivePolicy(hive_policy) => {
+            validate_delete_hive_policy(action, original_action, hive_policy)
+        }
+        EntryTypes::Hive(hive) => validate_delete_hive(action, original_action, hive),
+        EntryTypes::Invitation(invitation) => {
+            validate_delete_invitation(action, original_action, invitation)
+        }
+        EntryTypes::InvitationAcceptance(invitation_acceptance) => {
+            validate_delete_invitation_acceptance(action, original_action, invitation_acceptance)
+        }
+        EntryTypes::JoinRequest(join_request) => {
+            validate_delete_join_request(action, original_action, join_request)
+        }
+        EntryTypes::EntityProfile(entity_profile) => {
+            validate_delete_entity_profile(action, original_action, entity_profile)
+        }
+        EntryTypes::DeviceLinkClaim(device_link_claim) => {
+            validate_delete_device_link_claim(action, original_action, device_link_claim)
+        }
+        EntryTypes::DeviceLinkAcceptance(device_link_acceptance) => {
+            validate_delete_device_link_acceptance(action, original_action, device_link_acceptance)
+        }
+        EntryTypes::KeyEnvelope(key_envelope) => {
+            validate_delete_key_envelope(action, original_action, key_envelope)
+        }
+    }
+}
+fn validate_create_link_of_type(
+    link_type: LinkTypes,
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    match link_type {
+        LinkTypes::EncryptedContentUpdates => validate_create_link_encrypted_content_updates(
+            action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        // TODO
+        LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::HummContentOwner => {
+            validate_create_link_humm_content_owner(action, base_address, target_address, tag)
+        }
+        // TODO
+        LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::TimeItem => {
+            validate_create_link_time_item(action, base_address, target_address, tag)
+        }
+        // TODO
+        LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::OwnershipTransfers => {
+            validate_create_link_ownership_transfers(action, base_address, target_address, tag)
+        }
+        LinkTypes::AgentToOwnershipTransfers => {
+            validate_create_link_ownership_transfers(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveContentType => {
+            validate_create_link_hive_content_type(action, base_address, target_address, tag)
+        }
+        LinkTypes::GroupUpdates => {
+            validate_create_link_revision_updates(action, base_address, target_address, tag)
+        }
+        LinkTypes::GroupMember => {
+            validate_create_link_group_members(action, base_address, target_address, tag)
+        }
+        LinkTypes::HivePolicies => {
+            validate_create_link_hive_policies(action, base_address, target_address, tag)
+        }
+        LinkTypes::HivePolicyUpdates => {
+            validate_create_link_revision_updates(action, base_address, target_address, tag)
+        }
+        LinkTypes::AclInheritance => {
+            validate_create_link_acl_inheritance(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveUpdates => {
+            validate_create_link_revision_updates(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveMember => {
+            validate_create_link_hive_members(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveInvitations => {
+            validate_create_link_invitations(action, base_address, target_address, tag)
+        }
+        LinkTypes::AgentInvitations => {
+            validate_create_link_invitations(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveJoinRequests => {
+            validate_create_link_join_requests(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveDirectory => {
+            validate_create_link_hive_directory(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveSearch => {
+            validate_create_link_hive_directory(action, base_address, target_address, tag)
+        }
+        LinkTypes::EntityDirectory => {
+            validate_create_link_entity_directory(action, base_address, target_address, tag)
+        }
+        LinkTypes::AgentToEntityProfiles => {
+            validate_create_link_agent_to_entity_profiles(action, base_address, target_address, tag)
+        }
+        LinkTypes::EntityProfileUpdates => {
+            validate_create_link_revision_updates(action, base_address, target_address, tag)
+        }
+        LinkTypes::AgentToDeviceLinkClaims => {
+            validate_create_link_device_link_claims(action, base_address, target_address, tag)
+        }
+        LinkTypes::KeyEnvelopes => {
+            validate_create_link_key_envelopes(action, base_address, target_address, tag)
+        }
+        LinkTypes::HiveSubscribers => {
+            validate_create_link_hive_subscribers(action, base_address, target_address, tag)
+        }
+        LinkTypes::DynamicLinkValues => {
+            validate_create_link_dynamic_link_values(action, base_address, target_address, tag)
+        }
+        LinkTypes::AgentToInitialKey => {
+            validate_create_link_agent_to_initial_key(action, base_address, target_address, tag)
+        }
+    }
+}
+fn validate_delete_link_of_type(
+    link_type: LinkTypes,
+    action: DeleteLink,
+    original_action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    match link_type {
+        LinkTypes::EncryptedContentUpdates => validate_delete_link_encrypted_content_updates(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        // TODO
+        LinkTypes::OriginalHashPointer => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::HummContentOwner => validate_delete_link_humm_content_owner(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        // TODO
+        LinkTypes::HummContentAdmin => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentWriter => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentReader => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::Hive => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::HummContentId => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::TimePath => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::TimeItem => Ok(ValidateCallbackResult::Valid),
+        // TODO
+        LinkTypes::Dynamic => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::OwnershipTransfers => validate_delete_link_ownership_transfers(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AgentToOwnershipTransfers => validate_delete_link_ownership_transfers(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveContentType => validate_delete_link_hive_content_type(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::GroupUpdates => validate_delete_link_revision_updates(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::GroupMember => validate_delete_link_group_members(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HivePolicies => validate_delete_link_hive_policies(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HivePolicyUpdates => validate_delete_link_revision_updates(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AclInheritance => validate_delete_link_acl_inheritance(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveUpdates => validate_delete_link_revision_updates(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveMember => validate_delete_link_hive_members(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveInvitations => validate_delete_link_invitations(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AgentInvitations => validate_delete_link_invitations(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveJoinRequests => validate_delete_link_join_requests(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveDirectory => validate_delete_link_hive_directory(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveSearch => validate_delete_link_hive_directory(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::EntityDirectory => validate_delete_link_entity_directory(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AgentToEntityProfiles => validate_delete_link_agent_to_entity_profiles(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::EntityProfileUpdates => validate_delete_link_revision_updates(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AgentToDeviceLinkClaims => validate_delete_link_device_link_claims(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::KeyEnvelopes => validate_delete_link_key_envelopes(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::HiveSubscribers => validate_delete_link_hive_subscribers(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::DynamicLinkValues => validate_delete_link_dynamic_link_values(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+        LinkTypes::AgentToInitialKey => validate_delete_link_agent_to_initial_key(
+            action,
+            original_action,
+            base_address,
+            target_address,
+            tag,
+        ),
+    }
+}
@@ -0,0 +1,332 @@
+use hdi::{
+    hash_path::path::{Component, Path},
+    prelude::*,
+};
+
+use crate::{
+    must_get_authored_updates, must_get_encrypted_content, must_get_hive_policy,
+    must_get_preceding_acceptance, original_encrypted_content_hash, owner_agent_for_revision,
+    EncryptedContent, UnitEntryTypes, ACL_PARENT_INDEX,
+};
+
+/// A proposal by the current owner of an `EncryptedContent` to hand ownership to another entity.
+/// `content_hash` is the revision the proposal was made against, the accepted transfer must update
+/// exactly that revision; `new_owner_agent` is the agent that has to accept it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct OwnershipTransfer {
+    pub original_content_hash: ActionHash,
+    pub content_hash: ActionHash,
+    pub current_owner: String,
+    pub new_owner: String,
+    pub new_owner_agent: AgentPubKey,
+}
+
+/// The new owner's consent to an `OwnershipTransfer`, see `must_get_preceding_acceptance`.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct OwnershipTransferAcceptance {
+    pub ownership_transfer_hash: ActionHash,
+}
+
+pub fn validate_create_ownership_transfer(
+    action: EntryCreationAction,
+    ownership_transfer: OwnershipTransfer,
+) -> ExternResult<ValidateCallbackResult> {
+    let encrypted_content = must_get_encrypted_content(ownership_transfer.content_hash.clone())?;
+    if original_encrypted_content_hash(ownership_transfer.content_hash.clone())?
+        != ownership_transfer.original_content_hash
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The transferred revision does not belong to the original content",
+        )));
+    }
+    if encrypted_content.header.acl.owner != ownership_transfer.current_owner {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The current owner of the transfer does not match the content ACL",
+        )));
+    }
+    if ownership_transfer.new_owner == ownership_transfer.current_owner {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Ownership cannot be transferred to the current owner",
+        )));
+    }
+    if &owner_agent_for_revision(ownership_transfer.content_hash)? != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agent of the current owner can propose an ownership transfer",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_ownership_transfer(
+    _action: Update,
+    _ownership_transfer: OwnershipTransfer,
+    _original_action: EntryCreationAction,
+    _original_ownership_transfer: OwnershipTransfer,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Ownership transfers cannot be updated",
+    )))
+}
+pub fn validate_delete_ownership_transfer(
+    action: Delete,
+    original_action: EntryCreationAction,
+    _original_ownership_transfer: OwnershipTransfer,
+) -> ExternResult<ValidateCallbackResult> {
+    if &action.author != original_action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the proposer can cancel an ownership transfer",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_create_ownership_transfer_acceptance(
+    action: EntryCreationAction,
+    ownership_transfer_acceptance: OwnershipTransferAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    let ownership_transfer =
+        must_get_ownership_transfer(ownership_transfer_acceptance.ownership_transfer_hash)?;
+    if &ownership_transfer.new_owner_agent != action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the new owner agent can accept an ownership transfer",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_ownership_transfer_acceptance(
+    _action: Update,
+    _ownership_transfer_acceptance: OwnershipTransferAcceptance,
+    _original_action: EntryCreationAction,
+    _original_ownership_transfer_acceptance: OwnershipTransferAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Ownership transfer acceptances cannot be updated",
+    )))
+}
+pub fn validate_delete_ownership_transfer_acceptance(
+    _action: Delete,
+    _original_action: EntryCreationAction,
+    _original_ownership_transfer_acceptance: OwnershipTransferAcceptance,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Ownership transfer acceptances cannot be deleted",
+    )))
+}
+
+/// Checks an `EncryptedContent` update that changes `acl.owner`: the new owner must have accepted
+/// a transfer of the updated revision away from the previous owner, and the update may change
+/// nothing else but the citations of its author.
+pub fn validate_ownership_change(
+    action: &Update,
+    encrypted_content: &EncryptedContent,
+    original_encrypted_content: &EncryptedContent,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(acceptance) = must_get_preceding_acceptance::<OwnershipTransferAcceptance>(
+        action,
+        UnitEntryTypes::OwnershipTransferAcceptance,
+    )?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The owner of an EncryptedContent can only change through an accepted ownership transfer",
+        )));
+    };
+    let ownership_transfer = must_get_ownership_transfer(acceptance.ownership_transfer_hash)?;
+    if ownership_transfer.new_owner_agent != action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the new owner agent can commit an ownership change",
+        )));
+    }
+    if action.original_action_address != ownership_transfer.content_hash {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An ownership transfer can only update the revision it was proposed against",
+        )));
+    }
+    if original_encrypted_content.header.acl.owner != ownership_transfer.current_owner
+        || encrypted_content.header.acl.owner != ownership_transfer.new_owner
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The ownership change does not match the accepted ownership transfer",
+        )));
+    }
+    let mut expected = original_encrypted_content.clone();
+    expected.header.acl.owner = ownership_transfer.new_owner;
+    expected.header.hive_membership = encrypted_content.header.hive_membership.clone();
+    expected.header.author_entity_profile = encrypted_content.header.author_entity_profile.clone();
+    expected.header.author_group = encrypted_content.header.author_group.clone();
+    if &expected != encrypted_content {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An ownership transfer can only change the owner of the content",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn must_get_ownership_transfer(action_hash: ActionHash) -> ExternResult<OwnershipTransfer> {
+    let record = must_get_valid_record(action_hash)?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "Could not find the OwnershipTransfer"
+        ))))
+}
+
+pub fn validate_create_link_ownership_transfers(
+    _action: CreateLink,
+    _base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    must_get_ownership_transfer(action_hash)?;
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_delete_link_ownership_transfers(
+    action: DeleteLink,
+    original_action: CreateLink,
+    _base: AnyLinkableHash,
+    _target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if action.author != original_action.author {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of an ownership transfer link can delete it",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Owner links point from the ACL paths of the owner to the original hash of an `EncryptedContent`
+/// or `HivePolicy`. Only the agent that set the current owner links it, where the current owner
+/// is the one of the latest revision that agent committed.
+pub fn validate_create_link_humm_content_owner(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_hash =
+        target_address
+            .into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "No action hash associated with link"
+            ))))?;
+    let Some(owner_path_hashes) =
+        current_owner_path_hashes(&action.author, &action.prev_action, original_hash)?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agent of the current owner can link the owner",
+        )));
+    };
+    if !base_address
+        .into_entry_hash()
+        .map_or(false, |base| owner_path_hashes.contains(&base))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "An owner link must start from the path of the current owner",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// Owner links of a previous owner are deleted by the agent of the current owner. The owner links
+/// of a `HivePolicy` are also deleted by its creator when the policy is deleted.
+pub fn validate_delete_link_humm_content_owner(
+    action: DeleteLink,
+    _original_action: CreateLink,
+    base: AnyLinkableHash,
+    target: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_hash = target
+        .into_action_hash()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "No action hash associated with link"
+        ))))?;
+    let hive_policy_type: EntryType = UnitEntryTypes::HivePolicy.try_into()?;
+    let is_hive_policy = must_get_action(original_hash.clone())?
+        .action()
+        .entry_type()
+        == Some(&hive_policy_type);
+    let Some(owner_path_hashes) =
+        current_owner_path_hashes(&action.author, &action.prev_action, original_hash)?
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the agent of the current owner can unlink an owner",
+        )));
+    };
+    if !is_hive_policy
+        && base
+            .into_entry_hash()
+            .map_or(false, |base| owner_path_hashes.contains(&base))
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The owner link of the current owner cannot be deleted",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The hashes of the ACL paths of the owner set by the latest revision of `original_hash` that
+/// `author` committed up to `chain_top`. `None` if `author` didn't set that owner.
+fn current_owner_path_hashes(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    original_hash: ActionHash,
+) -> ExternResult<Option<Vec<EntryHash>>> {
+    let original_action = must_get_action(original_hash.clone())?;
+    let hive_policy_type: EntryType = UnitEntryTypes::HivePolicy.try_into()?;
+    let entry_type = if original_action.action().entry_type() == Some(&hive_policy_type) {
+        UnitEntryTypes::HivePolicy
+    } else {
+        UnitEntryTypes::EncryptedContent
+    };
+    let latest_revision = must_get_authored_updates(author, chain_top, entry_type, &original_hash)?
+        .into_iter()
+        .max_by_key(|(_, update)| update.action_seq)
+        .map(|(hash, _)| hash);
+    let latest_revision = match latest_revision {
+        Some(hash) => hash,
+        None if original_action.action().author() == author => original_hash,
+        None => return Ok(None),
+    };
+    if let UnitEntryTypes::HivePolicy = entry_type {
+        // only the creator of a policy writes it
+        if original_action.action().author() != author {
+            return Ok(None);
+        }
+        let hive_policy = must_get_hive_policy(latest_revision)?;
+        return Ok(Some(vec![acl_parent_path_hash(
+            &hive_policy.hive_id,
+            &hive_policy.acl.owner,
+        )?]));
+    }
+    if &owner_agent_for_revision(latest_revision.clone())? != author {
+        return Ok(None);
+    }
+    let header = must_get_encrypted_content(latest_revision)?.header;
+    Ok(Some(vec![
+        Path::from(vec![
+            Component::from(header.hive_id.clone()),
+            Component::from(header.content_type.clone()),
+            Component::from(header.acl.owner.clone()),
+        ])
+        .path_entry_hash()?,
+        acl_parent_path_hash(&header.hive_id, &header.acl.owner)?,
+    ]))
+}
+
+fn acl_parent_path_hash(hive_id: &str, principal: &str) -> ExternResult<EntryHash> {
+    Path::from(vec![
+        Component::from(ACL_PARENT_INDEX),
+        Component::from(hive_id.to_string()),
+        Component::from(principal.to_string()),
+    ])
+    .path_entry_hash()
+}
@@ -0,0 +1,25 @@
+use hdi::prelude::*;
+
+/// The properties of the DNA, `~` in `dna.yaml` for the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ContentProperties {
+    /// The key that assigns entity IDs. When set, an `EntityProfile` is only valid with its
+    /// signature, see `EntityClaim`, and content written for an entity must cite the profile of
+    /// its author.
+    #[serde(default)]
+    pub entity_authority: Option<AgentPubKey>,
+    /// Overrides `MAX_INDEX_TIME_BACKDATE_SECONDS`.
+    #[serde(default)]
+    pub max_index_time_backdate_seconds: Option<i64>,
+    /// Overrides `MAX_INDEX_TIME_FUTURE_SECONDS`.
+    #[serde(default)]
+    pub max_index_time_future_seconds: Option<i64>,
+}
+
+pub fn content_properties() -> ExternResult<ContentProperties> {
+    let properties = dna_info()?.modifiers.properties;
+    let properties: Option<ContentProperties> = ExternIO(properties.bytes().clone())
+        .decode()
+        .map_err(|e| wasm_error!(e))?;
+    Ok(properties.unwrap_or_default())
+}
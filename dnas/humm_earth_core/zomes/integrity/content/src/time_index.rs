@@ -0,0 +1,47 @@
+use hdi::prelude::*;
+
+use crate::{content_properties, MAX_INDEX_TIME_BACKDATE_SECONDS, MAX_INDEX_TIME_FUTURE_SECONDS};
+
+/// The part of the `TimedItemTag` of the time indexing library that validation reads. The tag is
+/// serialized as a map, so the fields not listed here are ignored.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct TimedItemTagTime {
+    pub devtest_timestamp: Timestamp,
+}
+
+/// The index time of a time item link must lie within the backdate and future bounds of the
+/// action time, see `ContentProperties`.
+pub fn validate_create_link_time_item(
+    action: CreateLink,
+    _base_address: AnyLinkableHash,
+    _target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(timed_item_tag) =
+        TimedItemTagTime::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner())))
+    else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "A TimeItem link must be tagged with a TimedItemTag",
+        )));
+    };
+    let properties = content_properties()?;
+    let max_backdate_seconds = properties
+        .max_index_time_backdate_seconds
+        .unwrap_or(MAX_INDEX_TIME_BACKDATE_SECONDS);
+    let max_future_seconds = properties
+        .max_index_time_future_seconds
+        .unwrap_or(MAX_INDEX_TIME_FUTURE_SECONDS);
+    let index_time = timed_item_tag.devtest_timestamp.as_micros();
+    let action_time = action.timestamp.as_micros();
+    if index_time < action_time.saturating_sub(max_backdate_seconds.saturating_mul(1_000_000)) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The index time is too far before the time of the action",
+        )));
+    }
+    if index_time > action_time.saturating_add(max_future_seconds.saturating_mul(1_000_000)) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "The index time is too far after the time of the action",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
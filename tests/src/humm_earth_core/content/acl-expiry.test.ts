@@ -0,0 +1,129 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64, encodeHashToBase64 } from "@holochain/client";
+
+import {
+  AclRole,
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("expired readers are not listed by list_by_acl_link", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent with one expired and one permanent reader
+    // (timestamps are in microseconds)
+    const acl = {
+      ...sampleEntityAcl(),
+      reader: ["test-expired-member-id", "test-member-id"],
+      expires_at: { "test-expired-member-id": (Date.now() - 60000) * 1000 },
+    };
+    const sampleContent = sampleEncryptedContent({ header: { acl } });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Wait for the created entry to be propagated to the other node.
+    await pause(1200);
+
+    const listAs = (entity_id: string): Promise<EncryptedContentResponse[]> =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_acl_link",
+        payload: {
+          hive_id: sampleContent.header.hive_id,
+          content_type: sampleContent.header.content_type,
+          acl_role: AclRole.Reader,
+          entity_id,
+        },
+      });
+    const canRead = (entity_id: string): Promise<boolean> =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "check_acl_permission",
+        payload: {
+          content_hash: decodeHashFromBase64(record.original_hash),
+          acl_role: AclRole.Reader,
+          entity_id,
+        },
+      });
+
+    assert.equal((await listAs("test-member-id")).length, 1);
+    assert.isTrue(await canRead("test-member-id"));
+    assert.equal((await listAs("test-expired-member-id")).length, 0);
+    assert.isFalse(await canRead("test-expired-member-id"));
+  });
+});
+
+test("content with time limited grants cannot be updated without an entity profile", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice creates a EncryptedContent with a time limited writer
+    const acl = {
+      ...sampleEntityAcl(),
+      writer: ["test-member-id"],
+      expires_at: { "test-member-id": (Date.now() + 60000) * 1000 },
+    };
+    const sampleContent = sampleEncryptedContent({ header: { acl } });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    // Without an entity profile the expiry cannot be checked
+    await expect(
+      async () =>
+        await alice.cells[0].callZome({
+          zome_name: "content",
+          fn_name: "update_encrypted_content",
+          payload: {
+            previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+            updated_encrypted_content: sampleContent,
+          },
+        })
+    ).rejects.toThrow();
+  });
+});
+
+test("an agent whose key grant expired cannot update the content", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent whose public key ACL gave Bob's key
+    // write access until a minute ago
+    const bobKey = encodeHashToBase64(bob.agentPubKey);
+    const sampleContent = sampleEncryptedContent({
+      header: {
+        acl: sampleEntityAcl(),
+        public_key_acl: {
+          owner: "test-entity-acl-public-key",
+          admin: [],
+          writer: [bobKey],
+          reader: [],
+          expires_at: { [bobKey]: (Date.now() - 60000) * 1000 },
+        },
+      },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    // Wait for the created entry to be propagated to the other node.
+    await pause(1200);
+
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "update_encrypted_content",
+        payload: {
+          previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+          updated_encrypted_content: sampleContent,
+        },
+      })
+    ).rejects.toThrow();
+  });
+});
@@ -0,0 +1,125 @@
+import { assert, expect, test } from "vitest";
+
+import { CallableCell, runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  AclRole,
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("content inherits the ACL of a hive policy and of its parent content", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a hive policy that grants read access to a member
+    const policy: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive_policy",
+      payload: {
+        hive_id: "test-hive-id",
+        acl: { ...sampleEntityAcl(), reader: ["test-member-id"] },
+        public_key_acl: sampleEntityAcl("test-entity-acl-public-key"),
+      },
+    });
+
+    // Alice creates a EncryptedContent inheriting from the policy, and a
+    // EncryptedContent of another type inheriting from the first one
+    const parentInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({ header: { acl: sampleEntityAcl() } })
+    );
+    const parent = await createEncryptedContent(alice.cells[0], {
+      ...parentInput,
+      inherits_from: {
+        type: "HivePolicy",
+        hash: decodeHashFromBase64(policy.hash),
+      },
+    });
+    assert.ok(parent);
+    const childInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({
+        header: { content_type: "test-other-type", acl: sampleEntityAcl() },
+      })
+    );
+    const child = await createEncryptedContent(alice.cells[0], {
+      ...childInput,
+      inherits_from: {
+        type: "Content",
+        hash: decodeHashFromBase64(parent.hash),
+      },
+    });
+    assert.ok(child);
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds both through the inherited grants
+    for (const content_type of ["test-content-type", "test-other-type"]) {
+      const contents: EncryptedContentResponse[] = await bob.cells[0].callZome(
+        {
+          zome_name: "content",
+          fn_name: "list_by_acl_link",
+          payload: {
+            hive_id: "test-hive-id",
+            content_type,
+            acl_role: AclRole.Reader,
+            entity_id: "test-member-id",
+          },
+        }
+      );
+      assert.equal(contents.length, 1);
+    }
+    const canRead: boolean = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "check_acl_permission",
+      payload: {
+        content_hash: decodeHashFromBase64(child.original_hash),
+        acl_role: AclRole.Reader,
+        entity_id: "test-member-id",
+      },
+    });
+    assert.isTrue(canRead);
+  });
+});
+
+test("only admins of a Hive can create its hive policies", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a hive with bob as a plain member
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [bob.agentPubKey],
+        membership_policy: "InviteOnly",
+      },
+    });
+    const createPolicy = (cell: CallableCell) =>
+      cell.callZome({
+        zome_name: "content",
+        fn_name: "create_hive_policy",
+        payload: {
+          hive_id: hive.hive_id,
+          acl: { ...sampleEntityAcl(), reader: ["test-member-id"] },
+          public_key_acl: sampleEntityAcl("test-entity-acl-public-key"),
+        },
+      });
+
+    assert.ok(await createPolicy(alice.cells[0]));
+
+    // Wait for the hive to be propagated to the other node.
+    await pause(1200);
+
+    await expect(
+      async () => await createPolicy(bob.cells[0])
+    ).rejects.toThrow();
+  });
+});
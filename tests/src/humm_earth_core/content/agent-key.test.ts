@@ -0,0 +1,42 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import {
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+test("agents are mapped to the initial key their content is indexed under", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice hasn't rotated any key, so the current key is the initial key
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    const initialKey = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_initial_agent_pub_key",
+      payload: alice.agentPubKey,
+    });
+    assert.deepEqual(initialKey, alice.agentPubKey);
+
+    // Wait for the created entry to be propagated to the other node.
+    await pause(1200);
+
+    const contents: EncryptedContentResponse[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_encrypted_content_by_time_and_author",
+      payload: {
+        author: alice.agentPubKey,
+        content_type: sampleInput.content_type,
+        start_time: null,
+        end_time: null,
+        limit: null,
+      },
+    });
+    assert.equal(contents.length, 1);
+  });
+});
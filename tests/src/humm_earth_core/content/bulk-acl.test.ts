@@ -0,0 +1,139 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import {
+  AclRole,
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("bulk add and remove a reader across a hive", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates two EncryptedContent of different types in the same hive
+    for (const content_type of ["test-content-type", "test-other-type"]) {
+      const sampleContent = sampleEncryptedContent({
+        header: { content_type, acl: sampleEntityAcl() },
+      });
+      const sampleInput = await sampleCreateEncryptedContentInput(
+        sampleContent
+      );
+      assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    }
+
+    // Alice adds a reader to everything in the hive
+    const bulkUpdate = (add: any[], remove: any[]) =>
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "bulk_update_acl",
+        payload: {
+          hive_id: "test-hive-id",
+          content_type: null,
+          add,
+          remove,
+          cursor: null,
+          batch_size: null,
+        },
+      });
+    const member = { acl_role: AclRole.Reader, entity_id: "test-member-id" };
+    const addReport: any = await bulkUpdate([member], []);
+    assert.equal(addReport.total, 2);
+    assert.equal(addReport.next_cursor, null);
+    assert.deepEqual(
+      addReport.items.map((item: any) => item.status),
+      ["Updated", "Updated"]
+    );
+
+    // Running the same update again changes nothing
+    const repeatReport: any = await bulkUpdate([member], []);
+    assert.deepEqual(
+      repeatReport.items.map((item: any) => item.status),
+      ["Unchanged", "Unchanged"]
+    );
+
+    // Wait for the updates to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds the content through the new reader's ACL link
+    const readerContent: EncryptedContentResponse[] =
+      await bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_acl_link",
+        payload: {
+          hive_id: "test-hive-id",
+          content_type: "test-content-type",
+          acl_role: AclRole.Reader,
+          entity_id: member.entity_id,
+        },
+      });
+    assert.equal(readerContent.length, 1);
+    assert.deepEqual(readerContent[0].encrypted_content.header.acl.reader, [
+      member.entity_id,
+    ]);
+
+    // Removing the reader again removes the ACL links
+    await bulkUpdate([], [member]);
+    await pause(1200);
+    const removedContent: EncryptedContentResponse[] =
+      await bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_acl_link",
+        payload: {
+          hive_id: "test-hive-id",
+          content_type: "test-content-type",
+          acl_role: AclRole.Reader,
+          entity_id: member.entity_id,
+        },
+      });
+    assert.equal(removedContent.length, 0);
+  });
+});
+
+test("bulk update pages through a hive one item at a time", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice creates three EncryptedContent in the same hive
+    for (let i = 0; i < 3; i++) {
+      const sampleInput = await sampleCreateEncryptedContentInput(
+        sampleEncryptedContent({ header: { acl: sampleEntityAcl() } })
+      );
+      assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    }
+
+    const bulkUpdate = (cursor: any, batch_size: number) =>
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "bulk_update_acl",
+        payload: {
+          hive_id: "test-hive-id",
+          content_type: "test-content-type",
+          add: [{ acl_role: AclRole.Reader, entity_id: "test-member-id" }],
+          remove: [],
+          cursor,
+          batch_size,
+        },
+      });
+
+    // A batch size of 0 would never finish
+    await expect(bulkUpdate(null, 0)).rejects.toThrow();
+
+    // Every content is updated exactly once across the pages
+    const updated: string[] = [];
+    let cursor = null;
+    do {
+      const report: any = await bulkUpdate(cursor, 1);
+      assert.equal(report.items.length, 1);
+      assert.equal(report.items[0].status, "Updated");
+      updated.push(report.items[0].original_hash);
+      cursor = report.next_cursor;
+    } while (cursor);
+    assert.equal(new Set(updated).size, 3);
+  });
+});
@@ -1,4 +1,4 @@
-import { CallableCell } from "@holochain/tryorama";
+import { CallableCell, Player, Scenario } from "@holochain/tryorama";
 import {
   NewEntryAction,
   ActionHash,
@@ -10,9 +10,28 @@ import {
   fakeDnaHash,
 } from "@holochain/client";
 
+// Installs the hApp for `count` players and registers all agents in every
+// conductor, so that tests don't wait for peer discovery through gossip.
+export async function addPlayers(
+  scenario: Scenario,
+  count: number
+): Promise<Player[]> {
+  const appSource = {
+    appBundleSource: {
+      path: process.cwd() + "/../workdir/humm-earth-core-happ.happ",
+    },
+  };
+  const players = await scenario.addPlayersWithApps(
+    Array(count).fill(appSource)
+  );
+  await scenario.shareAllAgents();
+  return players;
+}
+
 export type EncryptedContentResponse = {
   encrypted_content: any;
-  hash: ActionHash;
+  hash: string;
+  original_hash: string;
 };
 
 export enum AclRole {
@@ -34,6 +53,15 @@ export function sampleAcl() {
   };
 }
 
+export function sampleEntityAcl(owner = "test-entity-acl-id") {
+  return {
+    owner,
+    admin: [] as string[],
+    writer: [] as string[],
+    reader: [] as string[],
+  };
+}
+
 export function sampleEncryptedContent(partialEncryptedContent = {}) {
   return {
     bytes: Buffer.from("test-bytes"),
@@ -42,6 +70,7 @@ export function sampleEncryptedContent(partialEncryptedContent = {}) {
       id: "test-id",
       hive_id: "test-hive-id",
       content_type: "test-content-type",
+      revision_author_signing_public_key: "test-signing-public-key",
       acl: sampleAcl(),
       public_key_acl: {
         owner: "test-entity-acl-public-key",
@@ -63,6 +92,8 @@ export async function sampleCreateEncryptedContentInput(
     id: sample.header.id,
     hive_id: sample.header.hive_id,
     content_type: sample.header.content_type,
+    revision_author_signing_public_key:
+      sample.header.revision_author_signing_public_key,
     bytes: sample.bytes,
     acl: sample.header.acl,
     public_key_acl: sample.header.public_key_acl,
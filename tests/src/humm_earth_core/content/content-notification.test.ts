@@ -0,0 +1,97 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { encodeHashToBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("agents of the ACL are notified of new content", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Bob listens for remote signals
+    const signals: any[] = [];
+    bob.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+
+    // Alice creates a EncryptedContent readable by Bob's key
+    const sampleContent = sampleEncryptedContent({
+      header: {
+        public_key_acl: {
+          owner: encodeHashToBase64(alice.agentPubKey),
+          admin: [],
+          writer: [],
+          reader: [encodeHashToBase64(bob.agentPubKey)],
+        },
+      },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Wait for the notification to reach the other node.
+    await pause(1200);
+
+    const notification = signals.find(
+      (signal) => signal.type === "ContentNotification"
+    );
+    assert.ok(notification);
+    assert.equal(
+      encodeHashToBase64(notification.original_hash),
+      record.original_hash
+    );
+    assert.equal(notification.header.content_type, sampleInput.content_type);
+    assert.notProperty(notification, "bytes");
+  });
+});
+
+test("agents of an entity in the ACL are notified of new content", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Bob registers the entity Bob acts for, and listens for remote signals
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-member-id",
+        agents: [bob.agentPubKey],
+        encryption_public_keys: [],
+      },
+    });
+    const signals: any[] = [];
+    bob.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+
+    // Wait for the profile to be propagated to the other node.
+    await pause(1200);
+
+    // Alice creates a EncryptedContent readable by Bob's entity
+    const acl = sampleEntityAcl();
+    acl.reader.push("test-member-id");
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({ header: { acl } })
+    );
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    // Wait for the notification to reach the other node.
+    await pause(1200);
+
+    const notification = signals.find(
+      (signal) => signal.type === "ContentNotification"
+    );
+    assert.ok(notification);
+    assert.equal(
+      encodeHashToBase64(notification.original_hash),
+      record.original_hash
+    );
+  });
+});
@@ -0,0 +1,192 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64, encodeHashToBase64 } from "@holochain/client";
+
+import {
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("linked devices are treated as one author", async () => {
+  await runScenario(async (scenario) => {
+    const [laptop, phone] = await addPlayers(scenario, 2);
+
+    // The laptop registers the entity and claims the phone
+    const profile: any = await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-entity-acl-id",
+        agents: [laptop.agentPubKey],
+        encryption_public_keys: [],
+      },
+    });
+    await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "claim_device_link",
+      payload: {
+        entity_profile_hash: decodeHashFromBase64(profile.original_hash),
+        new_device: phone.agentPubKey,
+      },
+    });
+
+    // Wait for the claim to be propagated to the other node.
+    await pause(1200);
+
+    // The phone accepts the claim
+    const claims: any[] = await phone.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_my_device_link_claims",
+      payload: null,
+    });
+    assert.equal(claims.length, 1);
+    const linkedProfile: any = await phone.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "accept_device_link",
+      payload: decodeHashFromBase64(claims[0].hash),
+    });
+    assert.deepEqual(linkedProfile.entity_profile.agents, [
+      laptop.agentPubKey,
+      phone.agentPubKey,
+    ]);
+
+    // The phone creates content
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    assert.ok(await createEncryptedContent(phone.cells[0], sampleInput));
+
+    // Wait for the profile update and content to be propagated to the other node.
+    await pause(1200);
+
+    // The laptop finds the phone's content under its own key
+    const contents: EncryptedContentResponse[] =
+      await laptop.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_author",
+        payload: {
+          author: encodeHashToBase64(laptop.agentPubKey),
+          content_type: sampleInput.content_type,
+        },
+      });
+    assert.equal(contents.length, 1);
+    const timedContents: EncryptedContentResponse[] =
+      await laptop.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "get_encrypted_content_by_time_and_author",
+        payload: {
+          author: laptop.agentPubKey,
+          content_type: sampleInput.content_type,
+          start_time: null,
+          end_time: null,
+          limit: null,
+        },
+      });
+    assert.equal(timedContents.length, 1);
+  });
+});
+
+test("a revoked device is removed and its content can be reviewed", async () => {
+  await runScenario(async (scenario) => {
+    const [laptop, phone] = await addPlayers(scenario, 2);
+
+    // Devices cannot be added to a profile without accepting a claim
+    await expect(
+      laptop.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "create_entity_profile",
+        payload: {
+          entity_id: "test-entity-acl-id",
+          agents: [laptop.agentPubKey, phone.agentPubKey],
+          encryption_public_keys: [],
+        },
+      })
+    ).rejects.toThrow();
+
+    // The laptop registers the entity and links the phone
+    const profile: any = await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-entity-acl-id",
+        agents: [laptop.agentPubKey],
+        encryption_public_keys: [],
+      },
+    });
+    const claim: any = await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "claim_device_link",
+      payload: {
+        entity_profile_hash: decodeHashFromBase64(profile.original_hash),
+        new_device: phone.agentPubKey,
+      },
+    });
+
+    // Wait for the claim to be propagated to the other node.
+    await pause(1200);
+
+    await phone.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "accept_device_link",
+      payload: decodeHashFromBase64(claim.hash),
+    });
+
+    // The phone creates content before it is stolen
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    assert.ok(await createEncryptedContent(phone.cells[0], sampleInput));
+
+    // Wait for the content to be propagated to the other node.
+    await pause(1200);
+
+    // The laptop revokes the phone
+    const revokedProfile: any = await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "revoke_device",
+      payload: {
+        entity_profile_hash: decodeHashFromBase64(profile.original_hash),
+        device: phone.agentPubKey,
+        revoked_at: null,
+      },
+    });
+    assert.deepEqual(revokedProfile.entity_profile.agents, [
+      laptop.agentPubKey,
+    ]);
+    assert.deepEqual(
+      revokedProfile.entity_profile.revoked_agents.map((r: any) => r.agent),
+      [phone.agentPubKey]
+    );
+
+    // The laptop lists what the phone signed for review
+    const revokedContent: any[] = await laptop.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_content_by_revoked_device",
+      payload: {
+        entity_profile_hash: decodeHashFromBase64(profile.original_hash),
+        device: phone.agentPubKey,
+      },
+    });
+    assert.equal(revokedContent.length, 1);
+    assert.isFalse(revokedContent[0].after_revocation);
+
+    // Wait for the revocation to be propagated to the other node.
+    await pause(1200);
+
+    // The phone can no longer write for the entity, whether it cites the
+    // revision that still listed it or no profile at all
+    const entityInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({ header: { acl: sampleEntityAcl() } })
+    );
+    await expect(
+      createEncryptedContent(phone.cells[0], {
+        ...entityInput,
+        author_entity_profile: decodeHashFromBase64(profile.hash),
+      })
+    ).rejects.toThrow();
+    await expect(
+      createEncryptedContent(phone.cells[0], entityInput)
+    ).rejects.toThrow();
+  });
+});
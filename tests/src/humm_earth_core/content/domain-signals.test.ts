@@ -0,0 +1,112 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  AclRole,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("content changes are signalled with their domain context", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice listens for local signals
+    const signals: any[] = [];
+    alice.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+    const signalsOfType = (type: string) =>
+      signals.filter((signal) => signal.type === type);
+
+    // Alice creates a EncryptedContent
+    const sampleContent = sampleEncryptedContent({
+      header: { acl: sampleEntityAcl() },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Alice adds a reader
+    const updatedContent = {
+      ...record.encrypted_content,
+      header: {
+        ...record.encrypted_content.header,
+        acl: { ...sampleEntityAcl(), reader: ["test-member-id"] },
+      },
+    };
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+        updated_encrypted_content: updatedContent,
+      },
+    });
+
+    // Alice deletes the content
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "delete_encrypted_content",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+
+    // Wait for the signals to arrive.
+    await pause(500);
+
+    const [created] = signalsOfType("ContentCreated");
+    assert.equal(created.hive_id, sampleInput.hive_id);
+    assert.equal(created.content_type, sampleInput.content_type);
+    assert.equal(created.id, sampleInput.id);
+    assert.equal(signalsOfType("ContentUpdated").length, 1);
+    const [aclChanged] = signalsOfType("AclChanged");
+    assert.deepEqual(
+      aclChanged.added.map((change: any) => [change.acl_role, change.entity_id]),
+      [[AclRole.Reader, "test-member-id"]]
+    );
+    assert.deepEqual(aclChanged.removed, []);
+    assert.equal(signalsOfType("ContentDeleted").length, 1);
+  });
+});
+
+test("deleting content of another agent is signalled", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Bob listens for local signals
+    const signals: any[] = [];
+    bob.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+
+    // Alice creates a EncryptedContent
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({ header: { acl: sampleEntityAcl() } })
+    );
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    // Wait for the created entry to be propagated to the other node.
+    await pause(1200);
+
+    // Bob deletes it
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "delete_encrypted_content",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+
+    // Wait for the signals to arrive.
+    await pause(500);
+
+    const deleted = signals.filter(
+      (signal) => signal.type === "ContentDeleted"
+    );
+    assert.equal(deleted.length, 1);
+    assert.equal(deleted[0].id, sampleInput.id);
+  });
+});
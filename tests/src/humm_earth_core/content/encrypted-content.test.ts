@@ -442,3 +442,47 @@ test("create and read EncryptedContent by author link", async () => {
 //     ).rejects.toThrow();
 //   });
 // });
+
+test("read the latest EncryptedContent of a hive by time", async () => {
+  await runScenario(async (scenario) => {
+    // Construct proper paths for your app.
+    // This assumes app bundle created by the `hc app pack` command.
+    const testAppPath = process.cwd() + "/../workdir/humm-earth-core-happ.happ";
+
+    // Set up the app to be installed
+    const appSource = { appBundleSource: { path: testAppPath } };
+
+    // Add 2 players with the test app to the Scenario. The returned players
+    // can be destructured.
+    const [alice, bob] = await scenario.addPlayersWithApps([
+      appSource,
+      appSource,
+    ]);
+
+    // Shortcut peer discovery through gossip and register all agents in every
+    // conductor of the scenario.
+    await scenario.shareAllAgents();
+
+    // Alice and Bob both post in the hive
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    assert.ok(await createEncryptedContent(bob.cells[0], sampleInput));
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob reads the latest posts of the hive, whoever wrote them
+    const latest: EncryptedContentResponse[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_encrypted_content_by_time_and_hive",
+      payload: {
+        hive_id: sampleInput.hive_id,
+        content_type: sampleInput.content_type,
+        start_time: null,
+        end_time: null,
+        limit: null,
+      },
+    });
+    assert.equal(latest.length, 2);
+  });
+});
@@ -0,0 +1,128 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("entity profiles map entity IDs to the agents acting for them", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice registers the entity Alice acts for
+    const profile: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-entity-acl-id",
+        agents: [alice.agentPubKey],
+        encryption_public_keys: ["test-encryption-public-key"],
+      },
+    });
+    assert.ok(profile);
+
+    // Wait for the created entry to be propagated to the other node.
+    await pause(1200);
+
+    const agents: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_entity_agents",
+      payload: "test-entity-acl-id",
+    });
+    assert.deepEqual(agents, [alice.agentPubKey]);
+
+    // Alice writes content as the entity
+    const sampleInput = {
+      ...(await sampleCreateEncryptedContentInput(
+        sampleEncryptedContent({ header: { acl: sampleEntityAcl() } })
+      )),
+      author_entity_profile: decodeHashFromBase64(profile.hash),
+    };
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+
+    // Bob cannot cite a profile that does not list Bob
+    await expect(
+      createEncryptedContent(bob.cells[0], sampleInput)
+    ).rejects.toThrow();
+  });
+});
+
+test("entity profile updates are linked and resolved to the latest revision", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    const profile: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-entity-acl-id",
+        agents: [alice.agentPubKey],
+        encryption_public_keys: ["test-encryption-public-key"],
+      },
+    });
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_entity_profile",
+      payload: {
+        original_entity_profile_hash: decodeHashFromBase64(
+          profile.original_hash
+        ),
+        previous_entity_profile_hash: decodeHashFromBase64(profile.hash),
+        updated_entity_profile: {
+          ...profile.entity_profile,
+          encryption_public_keys: ["test-rotated-encryption-public-key"],
+        },
+      },
+    });
+
+    const latestProfile: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_entity_profile",
+      payload: "test-entity-acl-id",
+    });
+    assert.deepEqual(latestProfile.entity_profile.encryption_public_keys, [
+      "test-rotated-encryption-public-key",
+    ]);
+  });
+});
+
+test("entity profile updates must build on the latest revision", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    const profile: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-entity-acl-id",
+        agents: [alice.agentPubKey],
+        encryption_public_keys: ["test-encryption-public-key"],
+      },
+    });
+    const update = (encryptionPublicKey: string) =>
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "update_entity_profile",
+        payload: {
+          original_entity_profile_hash: decodeHashFromBase64(
+            profile.original_hash
+          ),
+          previous_entity_profile_hash: decodeHashFromBase64(profile.hash),
+          updated_entity_profile: {
+            ...profile.entity_profile,
+            encryption_public_keys: [encryptionPublicKey],
+          },
+        },
+      });
+    assert.ok(await update("test-rotated-encryption-public-key"));
+
+    // The original revision has been updated already
+    await expect(update("test-forked-encryption-public-key")).rejects.toThrow();
+  });
+});
@@ -0,0 +1,151 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  AclRole,
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("ACL group principals follow the group membership", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a group and gives it read access to a EncryptedContent
+    const group: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_group",
+      payload: {
+        name: "test-group",
+        owner: "test-entity-acl-id",
+        members: ["test-member-id"],
+      },
+    });
+    const acl = sampleEntityAcl();
+    acl.reader.push(group.principal);
+    const sampleContent = sampleEncryptedContent({ header: { acl } });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds the content through the member's group
+    const listAsMember = (): Promise<EncryptedContentResponse[]> =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_acl_link",
+        payload: {
+          hive_id: sampleContent.header.hive_id,
+          content_type: sampleContent.header.content_type,
+          acl_role: AclRole.Reader,
+          entity_id: "test-member-id",
+        },
+      });
+    assert.equal((await listAsMember()).length, 1);
+    const canRead: boolean = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "check_acl_permission",
+      payload: {
+        content_hash: decodeHashFromBase64(record.original_hash),
+        acl_role: AclRole.Reader,
+        entity_id: "test-member-id",
+      },
+    });
+    assert.isTrue(canRead);
+
+    // Alice removes the member from the group
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_group",
+      payload: {
+        original_group_hash: decodeHashFromBase64(group.original_hash),
+        previous_group_hash: decodeHashFromBase64(group.hash),
+        updated_group: { ...group.group, members: [] },
+      },
+    });
+
+    // Wait for the group update to be propagated to the other node.
+    await pause(1200);
+
+    assert.equal((await listAsMember()).length, 0);
+  });
+});
+
+test("entities write through a group only while they belong to it", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Bob registers the entity Bob acts for
+    const profile: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_entity_profile",
+      payload: {
+        entity_id: "test-member-id",
+        agents: [bob.agentPubKey],
+        encryption_public_keys: ["test-encryption-public-key"],
+      },
+    });
+
+    // Alice gives a group with Bob's entity write access to a EncryptedContent
+    const group: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_group",
+      payload: {
+        name: "test-group",
+        owner: "test-entity-acl-id",
+        members: ["test-member-id"],
+      },
+    });
+    const acl = sampleEntityAcl();
+    acl.writer.push(group.principal);
+    const sampleContent = sampleEncryptedContent({ header: { acl } });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob updates the content as a member of the group
+    const update = (previousHash: string) =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "update_encrypted_content",
+        payload: {
+          previous_encrypted_content_hash: decodeHashFromBase64(previousHash),
+          updated_encrypted_content: {
+            ...sampleContent,
+            header: {
+              ...sampleContent.header,
+              author_entity_profile: decodeHashFromBase64(profile.hash),
+            },
+          },
+        },
+      });
+    const updatedRecord: EncryptedContentResponse = await update(record.hash);
+    assert.ok(updatedRecord.encrypted_content.header.author_group);
+
+    // Alice removes Bob's entity from the group
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_group",
+      payload: {
+        original_group_hash: decodeHashFromBase64(group.original_hash),
+        previous_group_hash: decodeHashFromBase64(group.hash),
+        updated_group: { ...group.group, members: [] },
+      },
+    });
+
+    // Wait for the group update to be propagated to the other node.
+    await pause(1200);
+
+    await expect(update(updatedRecord.hash)).rejects.toThrow();
+  });
+});
@@ -0,0 +1,135 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("a hive exported by one agent can be imported by another", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent with a dynamic link and updates it
+    const sampleInput = await sampleCreateEncryptedContentInput({}, [
+      "test-dynamic-link",
+    ]);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+        updated_encrypted_content: {
+          ...record.encrypted_content,
+          bytes: Buffer.from("test-updated-bytes"),
+        },
+      },
+    });
+
+    // Alice exports the hive
+    const archive = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "export_hive",
+      payload: sampleInput.hive_id,
+    });
+
+    // Wait for the changes to be propagated to the other node.
+    await pause(1200);
+
+    // Bob imports the archive one item at a time
+    const imported: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "import_hive",
+      payload: { archive, cursor: null, batch_size: 1 },
+    });
+    assert.equal(imported.total, 1);
+    assert.equal(imported.next_cursor, null);
+    assert.equal(imported.items[0].original_hash, record.original_hash);
+    assert.equal(imported.items[0].revision_count, 2);
+
+    // The imported content is at its latest revision
+    const copy: EncryptedContentResponse = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_encrypted_content",
+      payload: decodeHashFromBase64(imported.items[0].imported_hash),
+    });
+    assert.deepEqual(
+      Buffer.from(copy.encrypted_content.bytes),
+      Buffer.from("test-updated-bytes")
+    );
+
+    // and listed under the same dynamic link
+    const byDynamicLink: EncryptedContentResponse[] =
+      await bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_dynamic_link",
+        payload: {
+          hive_id: sampleInput.hive_id,
+          content_type: sampleInput.content_type,
+          dynamic_link: "test-dynamic-link",
+        },
+      });
+    assert.equal(byDynamicLink.length, 2);
+  });
+});
+
+test("members import the content of a hive citing their membership", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates an open hive, and Bob joins it
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "Open",
+      },
+    });
+    await pause(1200);
+    const joinedHive: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "join_hive",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+
+    // Alice creates a EncryptedContent in the hive and exports the hive
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({
+        header: { hive_id: hive.hive_id, acl: sampleEntityAcl() },
+      })
+    );
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    const archive = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "export_hive",
+      payload: hive.hive_id,
+    });
+
+    // Wait for the changes to be propagated to the other node.
+    await pause(1200);
+
+    // Bob imports the archive citing the revision that lists Bob
+    const imported: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "import_hive",
+      payload: {
+        archive,
+        hive_membership: decodeHashFromBase64(joinedHive.hash),
+        cursor: null,
+        batch_size: null,
+      },
+    });
+    assert.equal(imported.items.length, 1);
+    assert.equal(imported.items[0].original_hash, record.original_hash);
+  });
+});
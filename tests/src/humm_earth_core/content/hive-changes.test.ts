@@ -0,0 +1,65 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+test("clients sync the changes of a hive since a timestamp", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates, updates and deletes a EncryptedContent
+    const since = Date.now() * 1000;
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    const updated: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+        updated_encrypted_content: {
+          ...record.encrypted_content,
+          bytes: Buffer.from("test-updated-bytes"),
+        },
+      },
+    });
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "delete_encrypted_content",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+
+    // Wait for the changes to be propagated to the other node.
+    await pause(1200);
+
+    // Bob syncs the hive page by page
+    const getChanges = (cursor: any): Promise<any> =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "get_hive_changes_since",
+        payload: { hive_id: sampleInput.hive_id, since, cursor, limit: 2 },
+      });
+    const firstPage = await getChanges(null);
+    assert.equal(firstPage.changes.length, 2);
+    assert.ok(firstPage.next_cursor);
+    const secondPage = await getChanges(firstPage.next_cursor);
+    assert.equal(secondPage.changes.length, 1);
+    assert.equal(secondPage.next_cursor, null);
+    const changes = [...firstPage.changes, ...secondPage.changes];
+    assert.deepEqual(
+      changes.map((change: any) => change.operation),
+      ["Create", "Update", "Delete"]
+    );
+    assert.equal(changes[1].hash, updated.hash);
+    assert.isTrue(
+      changes.every(
+        (change: any) => change.original_hash === record.original_hash
+      )
+    );
+  });
+});
@@ -0,0 +1,60 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause, CallableCell } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+test("hive digests match across agents and change with updates", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates two EncryptedContent
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    const getDigest = (cell: CallableCell): Promise<any> =>
+      cell.callZome({
+        zome_name: "content",
+        fn_name: "get_hive_digest",
+        payload: {
+          hive_id: sampleInput.hive_id,
+          content_type: null,
+          bucket_seconds: null,
+        },
+      });
+    const aliceDigest = await getDigest(alice.cells[0]);
+    const bobDigest = await getDigest(bob.cells[0]);
+    assert.equal(aliceDigest.item_count, 2);
+    assert.equal(aliceDigest.digest, bobDigest.digest);
+    assert.deepEqual(aliceDigest.buckets, bobDigest.buckets);
+
+    // Alice updates the first EncryptedContent
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+        updated_encrypted_content: {
+          ...record.encrypted_content,
+          bytes: Buffer.from("test-updated-bytes"),
+        },
+      },
+    });
+
+    // Wait for the update to be propagated to the other node.
+    await pause(1200);
+
+    const updatedDigest = await getDigest(bob.cells[0]);
+    assert.equal(updatedDigest.item_count, 2);
+    assert.notEqual(updatedDigest.digest, bobDigest.digest);
+  });
+});
@@ -0,0 +1,80 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import { addPlayers } from "./common.js";
+
+test("public hives are listed and searchable", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a public and a private hive
+    for (const visibility of ["Public", "Private"]) {
+      await alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "create_hive",
+        payload: {
+          name: `${visibility} Garden Club`,
+          description: "community gardening",
+          admins: [],
+          members: [],
+          membership_policy: "Open",
+          visibility,
+        },
+      });
+    }
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob only finds the public hive
+    const page: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_hives",
+      payload: { cursor: null, limit: null },
+    });
+    assert.equal(page.hives.length, 1);
+    assert.equal(page.next_cursor, null);
+    assert.equal(page.hives[0].hive.name, "Public Garden Club");
+
+    const search = (prefix: string): Promise<any[]> =>
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "search_hives",
+        payload: prefix,
+      });
+    assert.equal((await search("Gard")).length, 1);
+    assert.equal((await search("community")).length, 1);
+    assert.equal((await search("orchard")).length, 0);
+  });
+});
+
+test("hives can be found by words longer than a search token", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice creates a public hive with a word of 80 characters
+    const longWord = "pneumono".repeat(10);
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "Garden Club",
+        description: longWord,
+        admins: [],
+        members: [],
+        membership_policy: "Open",
+        visibility: "Public",
+      },
+    });
+
+    const search = (prefix: string): Promise<any[]> =>
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "search_hives",
+        payload: prefix,
+      });
+    assert.equal((await search(longWord.slice(0, 10))).length, 1);
+    assert.equal((await search(longWord)).length, 1);
+  });
+});
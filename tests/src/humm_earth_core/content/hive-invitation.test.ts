@@ -0,0 +1,171 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import { addPlayers } from "./common.js";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+test("invited agents join invite only hives", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Bob listens for remote signals
+    const signals: any[] = [];
+    bob.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+
+    // Alice creates an invite only hive and invites Bob
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "InviteOnly",
+      },
+    });
+    const invitation: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "invite_to_hive",
+      payload: {
+        hive_hash: decodeHashFromBase64(hive.original_hash),
+        invitee: bob.agentPubKey,
+      },
+    });
+    assert.ok(invitation);
+
+    // Wait for the invitation to be propagated to the other node.
+    await pause(1200);
+
+    assert.ok(signals.some((signal) => signal.type === "InvitationReceived"));
+    const invitations: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_my_invitations",
+      payload: null,
+    });
+    assert.equal(invitations.length, 1);
+
+    // Bob accepts and becomes a member
+    const joinedHive: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "accept_invitation",
+      payload: decodeHashFromBase64(invitations[0].hash),
+    });
+    assert.deepEqual(joinedHive.hive.members, [bob.agentPubKey]);
+
+    // The accepted invitation is closed and cannot be used again
+    const openInvitations: any[] = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_hive_invitations",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    assert.equal(openInvitations.length, 0);
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "accept_invitation",
+        payload: decodeHashFromBase64(invitations[0].hash),
+      })
+    ).rejects.toThrow();
+  });
+});
+
+test("admins accept join requests", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a hive that accepts join requests
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "RequestToJoin",
+      },
+    });
+
+    // Wait for the hive to be propagated to the other node.
+    await pause(1200);
+
+    // Bob asks to join
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "request_to_join_hive",
+      payload: {
+        hive_hash: decodeHashFromBase64(hive.original_hash),
+        message: "test-message",
+      },
+    });
+
+    // Wait for the join request to be propagated to the other node.
+    await pause(1200);
+
+    // Alice accepts the request
+    const joinRequests: any[] = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_join_requests",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    assert.equal(joinRequests.length, 1);
+    const joinedHive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "accept_join_request",
+      payload: decodeHashFromBase64(joinRequests[0].hash),
+    });
+    assert.deepEqual(joinedHive.hive.members, [bob.agentPubKey]);
+  });
+});
+
+test("revoked invitations are recorded in the hive and cannot be accepted", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice invites Bob to an invite only hive, then revokes the invitation
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "InviteOnly",
+      },
+    });
+    const invitation: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "invite_to_hive",
+      payload: {
+        hive_hash: decodeHashFromBase64(hive.original_hash),
+        invitee: bob.agentPubKey,
+      },
+    });
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "revoke_invitation",
+      payload: decodeHashFromBase64(invitation.hash),
+    });
+    const revokedHive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_hive",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    assert.deepEqual(revokedHive.hive.revoked_invitations, [
+      decodeHashFromBase64(invitation.hash),
+    ]);
+
+    // Wait for the revocation to be propagated to the other node.
+    await pause(1200);
+
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "accept_invitation",
+        payload: decodeHashFromBase64(invitation.hash),
+      })
+    ).rejects.toThrow();
+  });
+});
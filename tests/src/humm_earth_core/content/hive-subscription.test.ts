@@ -0,0 +1,106 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("hive subscribers receive live notifications", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates an open hive, and Bob joins it
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "Open",
+      },
+    });
+    await pause(1200);
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "join_hive",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    await pause(1200);
+
+    // Bob listens for remote signals and subscribes to the hive feed
+    const signals: any[] = [];
+    bob.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({
+        header: { hive_id: hive.hive_id, acl: sampleEntityAcl() },
+      })
+    );
+    const feed = {
+      hive_id: sampleInput.hive_id,
+      content_type: sampleInput.content_type,
+    };
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "subscribe_hive",
+      payload: feed,
+    });
+
+    // Wait for the subscription to be propagated to the other node.
+    await pause(1200);
+
+    // Alice creates a EncryptedContent in the hive
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    await pause(1200);
+    const notificationCount = () =>
+      signals.filter((signal) => signal.type === "ContentNotification").length;
+    assert.equal(notificationCount(), 1);
+
+    // After unsubscribing Bob no longer hears about new content
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "unsubscribe_hive",
+      payload: feed,
+    });
+    await pause(1200);
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+    await pause(1200);
+    assert.equal(notificationCount(), 1);
+  });
+});
+
+test("only hive members can subscribe to a hive", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates an invite only hive
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "InviteOnly",
+      },
+    });
+    await pause(1200);
+
+    // Bob isn't a member and can't subscribe
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "subscribe_hive",
+        payload: { hive_id: hive.hive_id, content_type: "test-content-type" },
+      })
+    ).rejects.toThrow();
+  });
+});
@@ -0,0 +1,153 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("only hive members can create content in a hive", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates an invite only hive and some content in it
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "InviteOnly",
+      },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({
+        header: { hive_id: hive.hive_id, acl: sampleEntityAcl() },
+      })
+    );
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+    assert.equal(
+      record.encrypted_content.header.hive_membership.toString(),
+      decodeHashFromBase64(hive.hash).toString()
+    );
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob is not a member, and cannot join without an invitation
+    await expect(
+      createEncryptedContent(bob.cells[0], sampleInput)
+    ).rejects.toThrow();
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "join_hive",
+        payload: decodeHashFromBase64(hive.original_hash),
+      })
+    ).rejects.toThrow();
+
+    // Alice adds Bob to the members
+    const updatedHive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_hive",
+      payload: {
+        original_hive_hash: decodeHashFromBase64(hive.original_hash),
+        previous_hive_hash: decodeHashFromBase64(hive.hash),
+        updated_hive: { ...hive.hive, members: [bob.agentPubKey] },
+      },
+    });
+    assert.ok(updatedHive);
+
+    // Wait for the hive update to be propagated to the other node.
+    await pause(1200);
+
+    assert.ok(await createEncryptedContent(bob.cells[0], sampleInput));
+    const bobsHives: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_my_hives",
+      payload: null,
+    });
+    assert.deepEqual(
+      bobsHives.map((hive) => hive.hive_id),
+      [hive.hive_id]
+    );
+  });
+});
+
+test("removing a member records the removal and unlinks the member", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates an open hive, and Bob joins it
+    const hive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "create_hive",
+      payload: {
+        name: "test-hive",
+        admins: [],
+        members: [],
+        membership_policy: "Open",
+      },
+    });
+    await pause(1200);
+    await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "join_hive",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    await pause(1200);
+
+    // Alice removes Bob
+    const joinedHive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_hive",
+      payload: decodeHashFromBase64(hive.original_hash),
+    });
+    const updatedHive: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_hive",
+      payload: {
+        original_hive_hash: decodeHashFromBase64(hive.original_hash),
+        previous_hive_hash: decodeHashFromBase64(joinedHive.hash),
+        updated_hive: { ...joinedHive.hive, members: [] },
+      },
+    });
+    assert.deepEqual(
+      updatedHive.hive.removals.map((removal: any) => removal.agent),
+      [bob.agentPubKey]
+    );
+
+    // Wait for the hive update to be propagated to the other node.
+    await pause(1200);
+
+    const bobsHives: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_my_hives",
+      payload: null,
+    });
+    assert.equal(bobsHives.length, 0);
+
+    // Bob can neither cite the revision that still listed Bob nor the latest one
+    const sampleInput = await sampleCreateEncryptedContentInput(
+      sampleEncryptedContent({
+        header: { hive_id: hive.hive_id, acl: sampleEntityAcl() },
+      })
+    );
+    await expect(
+      createEncryptedContent(bob.cells[0], {
+        ...sampleInput,
+        hive_membership: decodeHashFromBase64(joinedHive.hash),
+      })
+    ).rejects.toThrow();
+    await expect(
+      createEncryptedContent(bob.cells[0], sampleInput)
+    ).rejects.toThrow();
+  });
+});
@@ -0,0 +1,61 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import {
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+const DAY = 24 * 60 * 60 * 1000 * 1000;
+
+test("imported content is indexed at its original time", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice imports a EncryptedContent that was written 30 days ago
+    const now = Date.now() * 1000;
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    const record = await createEncryptedContent(alice.cells[0], {
+      ...sampleInput,
+      index_time: now - 30 * DAY,
+    });
+    assert.ok(record);
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds it in a time range that ends before the import
+    const imported: EncryptedContentResponse[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_encrypted_content_by_time_and_author",
+      payload: {
+        author: alice.agentPubKey,
+        content_type: sampleInput.content_type,
+        start_time: now - 31 * DAY,
+        end_time: now - 29 * DAY,
+        limit: null,
+      },
+    });
+    assert.equal(imported.length, 1);
+    assert.equal(imported[0].original_hash, record.original_hash);
+
+    // Index times far in the future are rejected
+    await expect(
+      createEncryptedContent(alice.cells[0], {
+        ...sampleInput,
+        index_time: now + DAY,
+      })
+    ).rejects.toThrow();
+
+    // So are index times before the backdating bound of the DNA properties
+    await expect(
+      createEncryptedContent(alice.cells[0], {
+        ...sampleInput,
+        index_time: now - 30 * 365 * DAY,
+      })
+    ).rejects.toThrow();
+  });
+});
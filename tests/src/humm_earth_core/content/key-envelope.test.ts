@@ -0,0 +1,180 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64, encodeHashToBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+} from "./common.js";
+
+test("readers of the public key ACL fetch their key envelope", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent with a key envelope for Bob
+    const sampleContent = sampleEncryptedContent({
+      header: {
+        public_key_acl: {
+          owner: encodeHashToBase64(alice.agentPubKey),
+          admin: [],
+          writer: [],
+          reader: [encodeHashToBase64(bob.agentPubKey)],
+        },
+      },
+    });
+    const sampleInput = {
+      ...(await sampleCreateEncryptedContentInput(sampleContent)),
+      key_envelopes: [
+        {
+          recipient: bob.agentPubKey,
+          wrapped_key: Buffer.from("test-wrapped-key"),
+        },
+      ],
+    };
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Wait for the created entries to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds the envelope addressed to him
+    const envelope: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_my_key_envelope",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+    assert.ok(envelope);
+    assert.deepEqual(envelope.key_envelope.recipient, bob.agentPubKey);
+
+    // Bob cannot issue a reader envelope for Bob's own key
+    await expect(
+      bob.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "create_key_envelopes",
+        payload: {
+          content_hash: decodeHashFromBase64(record.original_hash),
+          wrapped_keys: [
+            {
+              recipient: bob.agentPubKey,
+              wrapped_key: Buffer.from("test-wrapped-key"),
+            },
+          ],
+        },
+      })
+    ).rejects.toThrow();
+  });
+});
+
+test("removing a reader requires a rekey", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent readable by Bob's key
+    const sampleContent = sampleEncryptedContent({
+      header: {
+        public_key_acl: {
+          owner: encodeHashToBase64(alice.agentPubKey),
+          admin: [],
+          writer: [],
+          reader: [encodeHashToBase64(bob.agentPubKey)],
+        },
+      },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Removing Bob without a new key epoch is rejected
+    const withoutBob = {
+      ...record.encrypted_content,
+      header: {
+        ...record.encrypted_content.header,
+        public_key_acl: {
+          ...record.encrypted_content.header.public_key_acl,
+          reader: [],
+        },
+      },
+    };
+    await expect(
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "update_encrypted_content",
+        payload: {
+          previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+          updated_encrypted_content: withoutBob,
+        },
+      })
+    ).rejects.toThrow();
+
+    // Rekeying removes Bob and wraps the new key for Alice only
+    const rekeyed: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "rekey_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+        updated_encrypted_content: {
+          ...withoutBob,
+          bytes: Buffer.from("test-rekeyed-bytes"),
+        },
+        wrapped_keys: [
+          {
+            recipient: alice.agentPubKey,
+            wrapped_key: Buffer.from("test-new-wrapped-key"),
+          },
+        ],
+      },
+    });
+    assert.equal(rekeyed.encrypted_content.header.key_epoch, 1);
+
+    const envelope: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_my_key_envelope",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+    assert.equal(
+      encodeHashToBase64(envelope.key_envelope.content_hash),
+      rekeyed.hash
+    );
+
+    // Wait for the rekeyed revision to be propagated to the other node.
+    await pause(1200);
+
+    // Bob has no envelope for the content
+    const bobEnvelope: any = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_my_key_envelope",
+      payload: decodeHashFromBase64(record.original_hash),
+    });
+    assert.isNull(bobEnvelope);
+  });
+});
+
+test("content with readers that aren't agent keys cannot be rekeyed", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // The sample public key ACL is owned by a placeholder, not an agent key
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+
+    await expect(
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "rekey_encrypted_content",
+        payload: {
+          previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+          updated_encrypted_content: record.encrypted_content,
+          wrapped_keys: [
+            {
+              recipient: alice.agentPubKey,
+              wrapped_key: Buffer.from("test-new-wrapped-key"),
+            },
+          ],
+        },
+      })
+    ).rejects.toThrow();
+  });
+});
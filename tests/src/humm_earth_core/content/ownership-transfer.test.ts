@@ -0,0 +1,153 @@
+import { assert, expect, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  AclRole,
+  EncryptedContentResponse,
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+  sampleEncryptedContent,
+  sampleEntityAcl,
+} from "./common.js";
+
+test("transfer ownership of EncryptedContent to another agent", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent
+    const sampleContent = sampleEncryptedContent({
+      header: { acl: sampleEntityAcl() },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Alice proposes to hand the content over to bob
+    const transfer = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "transfer_ownership",
+      payload: {
+        content_hash: decodeHashFromBase64(record.original_hash),
+        new_owner: "test-new-owner-id",
+        new_owner_agent: bob.agentPubKey,
+      },
+    });
+    assert.ok(transfer);
+
+    // Wait for the proposal to be propagated to the other node.
+    await pause(1200);
+
+    // Bob finds and accepts the proposal
+    const transfers: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_my_ownership_transfers",
+      payload: null,
+    });
+    assert.equal(transfers.length, 1);
+    const accepted: EncryptedContentResponse = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "accept_ownership_transfer",
+      payload: decodeHashFromBase64(transfers[0].hash),
+    });
+    assert.equal(accepted.encrypted_content.header.acl.owner, "test-new-owner-id");
+
+    // Wait for the accepted transfer to be propagated to the other node.
+    await pause(1200);
+
+    // The owner link has moved to the new owner
+    const listByOwner = (entity_id: string) =>
+      alice.cells[0].callZome({
+        zome_name: "content",
+        fn_name: "list_by_acl_link",
+        payload: {
+          hive_id: sampleContent.header.hive_id,
+          content_type: sampleContent.header.content_type,
+          acl_role: AclRole.Owner,
+          entity_id,
+        },
+      });
+    const newOwnerContent: EncryptedContentResponse[] = await listByOwner(
+      "test-new-owner-id"
+    );
+    assert.equal(newOwnerContent.length, 1);
+    const previousOwnerContent: EncryptedContentResponse[] = await listByOwner(
+      sampleContent.header.acl.owner
+    );
+    assert.equal(previousOwnerContent.length, 0);
+  });
+});
+
+test("owner of EncryptedContent cannot change through a plain update", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice creates a EncryptedContent
+    const sampleContent = sampleEncryptedContent({
+      header: { acl: sampleEntityAcl() },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    assert.ok(record);
+
+    // Alice tries to hand the content over without a transfer
+    const contentUpdate = sampleEncryptedContent({
+      header: { acl: sampleEntityAcl("test-new-owner-id") },
+    });
+    await expect(
+      async () =>
+        await alice.cells[0].callZome({
+          zome_name: "content",
+          fn_name: "update_encrypted_content",
+          payload: {
+            previous_encrypted_content_hash: decodeHashFromBase64(record.hash),
+            updated_encrypted_content: contentUpdate,
+          },
+        })
+    ).rejects.toThrow();
+  });
+});
+
+test("cancelled ownership transfer cannot be accepted", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates a EncryptedContent and proposes to hand it over to bob
+    const sampleContent = sampleEncryptedContent({
+      header: { acl: sampleEntityAcl() },
+    });
+    const sampleInput = await sampleCreateEncryptedContentInput(sampleContent);
+    const record = await createEncryptedContent(alice.cells[0], sampleInput);
+    const transfer = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "transfer_ownership",
+      payload: {
+        content_hash: decodeHashFromBase64(record.original_hash),
+        new_owner: "test-new-owner-id",
+        new_owner_agent: bob.agentPubKey,
+      },
+    });
+
+    // Alice cancels the transfer
+    await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "cancel_ownership_transfer",
+      payload: decodeHashFromBase64(transfer.hash),
+    });
+
+    // Wait for the cancellation to be propagated to the other node.
+    await pause(1200);
+
+    // Bob can no longer accept the transfer
+    await expect(
+      async () =>
+        await bob.cells[0].callZome({
+          zome_name: "content",
+          fn_name: "accept_ownership_transfer",
+          payload: decodeHashFromBase64(transfer.hash),
+        })
+    ).rejects.toThrow();
+  });
+});
@@ -0,0 +1,67 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+import { decodeHashFromBase64 } from "@holochain/client";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+test("recently modified content appears once as its latest revision", async () => {
+  await runScenario(async (scenario) => {
+    const [alice, bob] = await addPlayers(scenario, 2);
+
+    // Alice creates two EncryptedContent and updates the first one
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    const first = await createEncryptedContent(alice.cells[0], sampleInput);
+    const second = await createEncryptedContent(alice.cells[0], sampleInput);
+    const updated: any = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "update_encrypted_content",
+      payload: {
+        previous_encrypted_content_hash: decodeHashFromBase64(first.hash),
+        updated_encrypted_content: {
+          ...first.encrypted_content,
+          bytes: Buffer.from("test-updated-bytes"),
+        },
+      },
+    });
+
+    // Wait for the changes to be propagated to the other node.
+    await pause(1200);
+
+    // Bob lists the hive, the updated content comes first and only once
+    const byHive: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_recently_modified",
+      payload: {
+        hive_id: sampleInput.hive_id,
+        author: null,
+        start_time: null,
+        end_time: null,
+        limit: null,
+      },
+    });
+    assert.deepEqual(
+      byHive.map((item) => item.encrypted_content.hash),
+      [updated.hash, second.hash]
+    );
+
+    // The same items are listed by author, up to the limit
+    const byAuthor: any[] = await bob.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "list_recently_modified",
+      payload: {
+        hive_id: null,
+        author: alice.agentPubKey,
+        start_time: null,
+        end_time: null,
+        limit: 1,
+      },
+    });
+    assert.equal(byAuthor.length, 1);
+    assert.equal(byAuthor[0].encrypted_content.hash, updated.hash);
+  });
+});
@@ -0,0 +1,47 @@
+import { assert, test } from "vitest";
+
+import { runScenario, pause } from "@holochain/tryorama";
+
+import {
+  addPlayers,
+  createEncryptedContent,
+  sampleCreateEncryptedContentInput,
+} from "./common.js";
+
+test("signals carry the encrypted bytes unless the zome property says otherwise", async () => {
+  await runScenario(async (scenario) => {
+    const [alice] = await addPlayers(scenario, 1);
+
+    // Alice listens for local signals
+    const signals: any[] = [];
+    alice.conductor
+      .appWs()
+      .on("signal", (signal: any) => signals.push(signal.payload));
+
+    // The bundled DNA sets no `signal_mode` property
+    const mode: string = await alice.cells[0].callZome({
+      zome_name: "content",
+      fn_name: "get_signal_mode",
+      payload: null,
+    });
+    assert.equal(mode, "Full");
+
+    // Alice creates a EncryptedContent
+    const sampleInput = await sampleCreateEncryptedContentInput();
+    assert.ok(await createEncryptedContent(alice.cells[0], sampleInput));
+
+    // Wait for the signals to arrive.
+    await pause(500);
+
+    assert.isTrue(
+      signals.some(
+        (signal) =>
+          signal.type === "EntryCreated" &&
+          signal.app_entry.type === "EncryptedContent"
+      )
+    );
+    assert.isFalse(
+      signals.some((signal) => signal.type === "EntryCreatedSummary")
+    );
+  });
+});
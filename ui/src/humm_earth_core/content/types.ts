@@ -22,12 +22,12 @@ export type ContentSignal =
       type: "EntryUpdated";
       action: SignedActionHashed<Update>;
       app_entry: EntryTypes;
-      original_app_entry: EntryTypes;
+      original_app_entry: EntryTypes | null;
     }
   | {
       type: "EntryDeleted";
       action: SignedActionHashed<Delete>;
-      original_app_entry: EntryTypes;
+      original_app_entry: EntryTypes | null;
     }
   | {
       type: "LinkCreated";
@@ -37,9 +37,80 @@ export type ContentSignal =
   | {
       type: "LinkDeleted";
       action: SignedActionHashed<DeleteLink>;
-      link_type: string;
+      link_type: string | null;
+    }
+  | {
+      type: "InvitationReceived";
+      invitation_hash: ActionHash;
+      invitation: Invitation;
+    }
+  | {
+      type: "InvitationRevoked";
+      invitation_hash: ActionHash;
+    }
+  | {
+      type: "JoinRequestReceived";
+      join_request_hash: ActionHash;
+      join_request: JoinRequest;
+    }
+  | {
+      type: "DeviceLinkClaimed";
+      device_link_claim_hash: ActionHash;
+      device_link_claim: DeviceLinkClaim;
+    }
+  | {
+      type: "ContentNotification";
+      content_hash: ActionHash;
+      original_hash: ActionHash;
+      author: AgentPubKey;
+      header: EncryptedContentHeader;
+    }
+  | {
+      type: "EntryCreatedSummary";
+      action: SignedActionHashed<Create>;
+      summary: EncryptedContentSummary;
+    }
+  | {
+      type: "EntryUpdatedSummary";
+      action: SignedActionHashed<Update>;
+      summary: EncryptedContentSummary;
+    }
+  | {
+      type: "EntryDeletedSummary";
+      action: SignedActionHashed<Delete>;
+      original_summary: EncryptedContentSummary;
+    }
+  | {
+      type: "ContentCreated";
+      hive_id: string;
+      content_type: string;
+      id: string;
+      hash: ActionHash;
+    }
+  | {
+      type: "ContentUpdated";
+      hive_id: string;
+      content_type: string;
+      id: string;
+      hash: ActionHash;
+      original_hash: ActionHash;
+    }
+  | {
+      type: "ContentDeleted";
+      hive_id: string;
+      content_type: string;
+      id: string;
+      hash: ActionHash;
+    }
+  | {
+      type: "AclChanged";
+      original_hash: ActionHash;
+      added: AclRoleEntity[];
+      removed: AclRoleEntity[];
     };
 
+export type SignalMode = "Full" | "HeaderOnly";
+
 export type EntryTypes = { type: "EncryptedContent" } & EncryptedContent;
 
 export interface EncryptedContent {
@@ -49,3 +120,61 @@ export interface EncryptedContent {
 
   bytes: Array<number>;
 }
+
+export interface EncryptedContentHeader {
+  id: string;
+
+  hive_id: string;
+
+  content_type: string;
+
+  key_epoch: number;
+}
+
+export interface AclRoleEntity {
+  acl_role: string;
+
+  entity_id: string;
+
+  expires_at?: number;
+}
+
+export interface EncryptedContentSummary {
+  header: EncryptedContentHeader;
+
+  bytes_length: number;
+}
+
+export interface Invitation {
+  hive_hash: ActionHash;
+
+  hive_revision: ActionHash;
+
+  invitee: AgentPubKey;
+}
+
+export interface JoinRequest {
+  hive_hash: ActionHash;
+
+  hive_revision: ActionHash;
+
+  requester: AgentPubKey;
+
+  message: string;
+}
+
+export interface DeviceLinkClaim {
+  entity_profile_hash: ActionHash;
+
+  entity_profile_revision: ActionHash;
+
+  new_device: AgentPubKey;
+}
+
+export interface KeyEnvelope {
+  content_hash: ActionHash;
+
+  recipient: AgentPubKey;
+
+  wrapped_key: Uint8Array;
+}